@@ -0,0 +1,417 @@
+//! Adapters that turn score and approval ballots into ranked ballots,
+//! so they can be tallied by SumMatrix and the ranked methods built on it.
+
+use std::collections::BTreeSet;
+
+use election::{Election, Ballot, BallotProblem, ElectionParseError, parse_line};
+use rational::Rational;
+use sum_matrix::SumMatrix;
+use scores::rank_by;
+
+/// What a score ballot means for candidates it doesn't mention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unlisted
+{
+    /// Leave them off the ranked ballot.
+    Unranked,
+    /// Treat them as given a score of 0.
+    Zero
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreOptions
+{
+    /// Scores must be between 0 and max_score.
+    pub max_score: Rational,
+    /// Leave zero-scored candidates unranked instead of ranking them last.
+    pub drop_zero: bool,
+    pub unlisted: Unlisted
+}
+
+impl Default for ScoreOptions
+{
+    /// 0–10 scores, zeros ranked last, unlisted candidates unranked.
+    fn default() -> Self
+    {
+        ScoreOptions { max_score: Rational::from(10), drop_zero: false, unlisted: Unlisted::Unranked }
+    }
+}
+
+/// Score ballots, converted to rankings where a higher score means a higher rank
+/// and equal scores mean equal rank.
+pub struct ScoreBallots
+{
+    options: ScoreOptions,
+    candidates: BTreeSet<String>,
+    ballots: Vec<(Rational, Vec<(String, Rational)>)>
+}
+
+impl ScoreBallots
+{
+    pub fn new(options: ScoreOptions) -> Self
+    {
+        ScoreBallots { options: options, candidates: BTreeSet::new(), ballots: Vec::new() }
+    }
+
+    /// Adds a ballot giving each listed candidate a score.
+    pub fn add_ballot(&mut self, weight: Rational, scores: Vec<(String, Rational)>) -> Result<(), ElectionParseError>
+    {
+        self.check_ballot(&scores)?;
+        self.push_ballot(weight, scores);
+        Ok(())
+    }
+
+    fn check_ballot(&self, scores: &[(String, Rational)]) -> Result<(), ElectionParseError>
+    {
+        let mut seen = BTreeSet::new();
+
+        for &(ref cand, score) in scores
+        {
+            if cand.is_empty()
+            {
+                return Err(ElectionParseError::InvalidBallotError(BallotProblem::EmptyRank));
+            }
+            if !seen.insert(cand)
+            {
+                return Err(ElectionParseError::InvalidBallotError(BallotProblem::DuplicateCandidate));
+            }
+            if score.is_negative() || score > self.options.max_score
+            {
+                return Err(ElectionParseError::ScoreRangeError);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn push_ballot(&mut self, weight: Rational, scores: Vec<(String, Rational)>)
+    {
+        for &(ref cand, _) in &scores
+        {
+            self.candidates.insert(cand.clone());
+        }
+
+        self.ballots.push((weight, scores));
+    }
+
+    /// Parses score ballots.
+    /// Format: Per line:
+    /// [amount]:[candidate]=[score],[candidate]=[score],...
+    /// e.g. "3:A=10,B=7,C=0"
+    /// Nothing is added unless every line is valid.
+    pub fn add_ballots(&mut self, data: &str) -> Result<(), ElectionParseError>
+    {
+        let mut ballots = Vec::new();
+
+        for line in data.trim().lines()
+        {
+            let (amount, vote) = parse_line(line)?;
+            let mut scores = Vec::new();
+
+            for part in vote.split(',').filter(|p| !p.trim().is_empty())
+            {
+                let mut pieces = part.split('=');
+
+                let cand = pieces.next().unwrap_or("").trim();
+
+                let score = match pieces.next()
+                {
+                    Some(score) => score,
+                    None => return Err(ElectionParseError::NoScoreError)
+                };
+
+                if pieces.next().is_some()
+                {
+                    return Err(ElectionParseError::ExcessDataError);
+                }
+
+                let score = match score.parse::<Rational>()
+                {
+                    Ok(score) => score,
+                    Err(e) => return Err(ElectionParseError::ScoreParseError(e))
+                };
+
+                scores.push((cand.to_owned(), score));
+            }
+
+            self.check_ballot(&scores)?;
+            ballots.push((amount, scores));
+        }
+
+        for (amount, scores) in ballots
+        {
+            self.push_ballot(amount, scores);
+        }
+
+        Ok(())
+    }
+
+    /// Converts a single score ballot to ranks.
+    fn ranks(&self, scores: &[(String, Rational)]) -> Vec<Vec<String>>
+    {
+        let mut scores = scores.to_vec();
+
+        if self.options.unlisted == Unlisted::Zero
+        {
+            for cand in &self.candidates
+            {
+                if !scores.iter().any(|&(ref c, _)| c == cand)
+                {
+                    scores.push((cand.clone(), Rational::zero()));
+                }
+            }
+        }
+
+        if self.options.drop_zero
+        {
+            scores.retain(|&(_, score)| !score.is_zero());
+        }
+
+        rank_by(scores)
+    }
+
+    pub fn to_election(&self) -> Election
+    {
+        let mut election = Election::new();
+
+        for &(weight, ref scores) in &self.ballots
+        {
+            election.ballots.push(Ballot::new(weight, self.ranks(scores)));
+        }
+
+        election
+    }
+
+    pub fn to_sum_matrix(&self) -> SumMatrix
+    {
+        SumMatrix::new(&self.to_election())
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApprovalOptions
+{
+    /// Rank unapproved candidates in a second tier (A=B>C=D).
+    /// If false, they are left unranked (A=B).
+    pub rank_unapproved: bool
+}
+
+impl Default for ApprovalOptions
+{
+    fn default() -> Self
+    {
+        ApprovalOptions { rank_unapproved: true }
+    }
+}
+
+/// Approval ballots, converted to two-tier rankings: approved candidates above everyone else.
+pub struct ApprovalBallots
+{
+    options: ApprovalOptions,
+    candidates: BTreeSet<String>,
+    ballots: Vec<(Rational, Vec<String>)>
+}
+
+impl ApprovalBallots
+{
+    pub fn new(options: ApprovalOptions) -> Self
+    {
+        ApprovalBallots { options: options, candidates: BTreeSet::new(), ballots: Vec::new() }
+    }
+
+    /// Declares candidates up front, so that ones nobody approved still get ranked in the lower tier.
+    pub fn with_candidates<I, S>(mut self, candidates: I) -> Self
+        where
+        I: IntoIterator<Item=S>,
+        S: Into<String>
+    {
+        self.candidates.extend(candidates.into_iter().map(|c| c.into()));
+        self
+    }
+
+    pub fn add_ballot(&mut self, weight: Rational, approved: Vec<String>) -> Result<(), ElectionParseError>
+    {
+        ApprovalBallots::check_ballot(&approved)?;
+        self.push_ballot(weight, approved);
+        Ok(())
+    }
+
+    fn check_ballot(approved: &[String]) -> Result<(), ElectionParseError>
+    {
+        let mut seen = BTreeSet::new();
+
+        for cand in approved
+        {
+            if cand.is_empty()
+            {
+                return Err(ElectionParseError::InvalidBallotError(BallotProblem::EmptyRank));
+            }
+            if !seen.insert(cand)
+            {
+                return Err(ElectionParseError::InvalidBallotError(BallotProblem::DuplicateCandidate));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn push_ballot(&mut self, weight: Rational, approved: Vec<String>)
+    {
+        self.candidates.extend(approved.iter().cloned());
+        self.ballots.push((weight, approved));
+    }
+
+    /// Parses approval ballots.
+    /// Format: Per line:
+    /// [amount]:[candidate],[candidate],...
+    /// e.g. "3:A,B". An empty list approves no one.
+    /// Nothing is added unless every line is valid.
+    pub fn add_ballots(&mut self, data: &str) -> Result<(), ElectionParseError>
+    {
+        let mut ballots = Vec::new();
+
+        for line in data.trim().lines()
+        {
+            let (amount, vote) = parse_line(line)?;
+
+            let approved: Vec<String> = vote.split(',')
+                .map(|c| c.trim())
+                .filter(|c| !c.is_empty())
+                .map(|c| c.to_owned())
+                .collect();
+
+            ApprovalBallots::check_ballot(&approved)?;
+            ballots.push((amount, approved));
+        }
+
+        for (amount, approved) in ballots
+        {
+            self.push_ballot(amount, approved);
+        }
+
+        Ok(())
+    }
+
+    fn ranks(&self, approved: &[String]) -> Vec<Vec<String>>
+    {
+        let mut top = approved.to_vec();
+        top.sort();
+
+        let rest: Vec<String> = self.candidates.iter().filter(|c| !approved.contains(c)).cloned().collect();
+
+        let mut ranks = Vec::new();
+
+        if !top.is_empty()
+        {
+            ranks.push(top);
+        }
+        if self.options.rank_unapproved && !rest.is_empty()
+        {
+            ranks.push(rest);
+        }
+
+        ranks
+    }
+
+    pub fn to_election(&self) -> Election
+    {
+        let mut election = Election::new();
+
+        for &(weight, ref approved) in &self.ballots
+        {
+            election.ballots.push(Ballot::new(weight, self.ranks(approved)));
+        }
+
+        election
+    }
+
+    pub fn to_sum_matrix(&self) -> SumMatrix
+    {
+        SumMatrix::new(&self.to_election())
+    }
+}
+
+
+#[cfg(test)]
+mod ballot_adapters_tests
+{
+    use super::*;
+
+    fn ranks(data: &[&[&str]]) -> Vec<Vec<String>>
+    {
+        data.iter().map(|rank| rank.iter().map(|c| c.to_string()).collect()).collect()
+    }
+
+    #[test]
+    fn scores_to_ranks()
+    {
+        let mut scores = ScoreBallots::new(ScoreOptions::default());
+
+        scores.add_ballots("
+        2:A=10,B=7,C=7,D=0
+        1:B=3").unwrap();
+
+        let election = scores.to_election();
+
+        assert_eq!(election.votes()[0].ranks, ranks(&[&["A"], &["B", "C"], &["D"]]));
+        assert_eq!(election.votes()[1].ranks, ranks(&[&["B"]]));
+
+        let options = ScoreOptions { drop_zero: true, unlisted: Unlisted::Zero, ..ScoreOptions::default() };
+        let mut scores = ScoreBallots::new(options);
+
+        scores.add_ballots("
+        2:A=10,B=7,C=7,D=0
+        1:B=3").unwrap();
+
+        let election = scores.to_election();
+
+        assert_eq!(election.votes()[0].ranks, ranks(&[&["A"], &["B", "C"]]));
+        assert_eq!(election.votes()[1].ranks, ranks(&[&["B"]]));
+
+        match scores.add_ballots("1:A=11")
+        {
+            Err(ElectionParseError::ScoreRangeError) => (),
+            other => panic!("Expected a score range error, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn failed_add_keeps_ballots()
+    {
+        let mut scores = ScoreBallots::new(ScoreOptions::default());
+        scores.add_ballots("1:A=10,B=5").unwrap();
+
+        assert!(scores.add_ballots("
+        2:C=3
+        1:A=11").is_err());
+
+        assert_eq!(scores.to_election().votes().len(), 1);
+        assert!(!scores.candidates.contains("C"));
+
+        let mut approvals = ApprovalBallots::new(ApprovalOptions::default());
+        approvals.add_ballots("1:A").unwrap();
+
+        assert!(approvals.add_ballots("
+        2:B
+        1:A,A").is_err());
+
+        assert_eq!(approvals.to_election().votes().len(), 1);
+        assert!(!approvals.candidates.contains("B"));
+    }
+
+    #[test]
+    fn approvals_to_ranks()
+    {
+        let mut approvals = ApprovalBallots::new(ApprovalOptions::default()).with_candidates(vec!["E"]);
+
+        approvals.add_ballots("
+        3:B,A
+        1:C,D").unwrap();
+
+        let election = approvals.to_election();
+
+        assert_eq!(election.votes()[0].ranks, ranks(&[&["A", "B"], &["C", "D", "E"]]));
+        assert_eq!(election.votes()[1].ranks, ranks(&[&["C", "D"], &["A", "B", "E"]]));
+    }
+}
@@ -0,0 +1,422 @@
+use election::{Election, Ballot};
+use sum_matrix::SumMatrix;
+use rational::Rational;
+use rounds::{Round, EliminationResult};
+use scores::rank_by;
+
+
+/// How a ballot gives points to the candidates it leaves unranked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Truncation
+{
+    /// Unranked candidates share the points for the places left at the bottom, as if ranked equal last.
+    /// On complete ballots without equal ranks, a candidate's Borda score is then their total pairwise wins.
+    Average,
+    /// Unranked candidates get no points. Ranked candidates still get a point for each unranked candidate.
+    Zero
+}
+
+/**
+Gets each candidate's Borda score, counting only 'candidates' (others are ignored as if not on the ballots).
+On each ballot a candidate gets a point for each candidate ranked below them, and half a point
+for each candidate ranked equal with them, so equal ranks share the points of the places they cover.
+With n candidates, a candidate ranked alone at the top gets n - 1 points.
+*/
+pub fn borda_scores(election: &Election, candidates: &[String], truncation: Truncation) -> Vec<(String, Rational)>
+{
+    let mut scores: Vec<(String, Rational)> = candidates.iter().map(|c| (c.clone(), Rational::zero())).collect();
+
+    for ballot in election.votes()
+    {
+        for (cand, points) in ballot_points(ballot, candidates, truncation)
+        {
+            scores[cand].1 += ballot.weight * points;
+        }
+    }
+
+    scores
+}
+
+/// The points one ballot gives, as (index into 'candidates', points).
+fn ballot_points(ballot: &Ballot, candidates: &[String], truncation: Truncation) -> Vec<(usize, Rational)>
+{
+    let ranks: Vec<Vec<usize>> = ballot.ranks.iter()
+        .map(|rank| rank.iter().filter_map(|name| candidates.iter().position(|c| c == name)).collect::<Vec<usize>>())
+        .filter(|rank| !rank.is_empty())
+        .collect();
+
+    let ranked: usize = ranks.iter().map(|rank| rank.len()).sum();
+    let mut below = candidates.len() - ranked;
+    let mut points = Vec::new();
+
+    if truncation == Truncation::Average
+    {
+        let unranked = Rational::new(below as i128, 1);
+        let tied = if below > 0 { (unranked - Rational::one()) / Rational::new(2, 1) } else { Rational::zero() };
+
+        for cand in (0..candidates.len()).filter(|c| !ranks.iter().any(|rank| rank.contains(c)))
+        {
+            points.push((cand, tied));
+        }
+    }
+
+    for rank in ranks.iter().rev()
+    {
+        let tied = Rational::new(rank.len() as i128 - 1, 2);
+
+        for &cand in rank
+        {
+            points.push((cand, Rational::from(below) + tied));
+        }
+
+        below += rank.len();
+    }
+
+    points
+}
+
+/**
+The Borda count: the candidates with the most points win.
+It isn't a Condorcet method, but it's the basis of Nanson and Baldwin, which are.
+*/
+pub struct Borda
+{
+    election: Election,
+    truncation: Truncation
+}
+
+impl Borda
+{
+    pub fn with_election(election: &Election) -> Self
+    {
+        Borda{ election: election.clone(), truncation: Truncation::Average }
+    }
+
+    /// Sets how truncated ballots are counted (Truncation::Average by default).
+    pub fn truncation(mut self, truncation: Truncation) -> Self
+    {
+        self.truncation = truncation;
+        self
+    }
+
+    pub fn get_result(&self) -> BordaResult
+    {
+        let scores = borda_scores(&self.election, &self.election.candidates(), self.truncation);
+        let ranking = rank_by(scores.clone());
+        let winners = ranking.first().cloned().unwrap_or_default();
+
+        BordaResult { scores: scores, ranking: ranking, winners: winners }
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+/// The outcome of a Borda count.
+pub struct BordaResult
+{
+    /// Each candidate's points, by name.
+    pub scores: Vec<(String, Rational)>,
+    /// The candidates from most points to fewest; candidates with equal points share a rank.
+    pub ranking: Vec<Vec<String>>,
+    pub winners: Vec<String>
+}
+
+/**
+Black's method elects the Condorcet winner if there is one, and otherwise the Borda winners.
+*/
+pub struct Black
+{
+    election: Election,
+    truncation: Truncation
+}
+
+impl Black
+{
+    pub fn with_election(election: &Election) -> Self
+    {
+        Black{ election: election.clone(), truncation: Truncation::Average }
+    }
+
+    /// Sets how truncated ballots are counted for the Borda count (Truncation::Average by default).
+    pub fn truncation(mut self, truncation: Truncation) -> Self
+    {
+        self.truncation = truncation;
+        self
+    }
+
+    pub fn get_result(&self) -> BlackResult
+    {
+        let condorcet_winner = SumMatrix::new(&self.election).condorcet_winner();
+        let borda = Borda::with_election(&self.election).truncation(self.truncation).get_result();
+
+        // The Condorcet winner comes first, and the Borda count orders everyone else.
+        let ranking = match condorcet_winner
+        {
+            Some(ref winner) => {
+                let mut ranking = vec![vec![winner.clone()]];
+                ranking.extend(borda.ranking.iter()
+                    .map(|rank| rank.iter().filter(|&c| c != winner).cloned().collect::<Vec<String>>())
+                    .filter(|rank| !rank.is_empty()));
+                ranking
+            },
+            None => borda.ranking.clone()
+        };
+
+        let winners = ranking.first().cloned().unwrap_or_default();
+
+        BlackResult { condorcet_winner: condorcet_winner, borda: borda, ranking: ranking, winners: winners }
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+/// The outcome of Black's method.
+pub struct BlackResult
+{
+    pub condorcet_winner: Option<String>,
+    /// The Borda count, which decides if there's no Condorcet winner.
+    pub borda: BordaResult,
+    pub ranking: Vec<Vec<String>>,
+    pub winners: Vec<String>
+}
+
+/**
+Nanson's method repeats the Borda count, each round eliminating every candidate at or below the average score,
+until one candidate is left. It always elects the Condorcet winner, who is always above average.
+If all the remaining candidates have the same score, they tie.
+*/
+pub struct Nanson
+{
+    election: Election,
+    truncation: Truncation
+}
+
+impl Nanson
+{
+    pub fn with_election(election: &Election) -> Self
+    {
+        Nanson{ election: election.clone(), truncation: Truncation::Average }
+    }
+
+    /// Sets how truncated ballots are counted (Truncation::Average by default).
+    pub fn truncation(mut self, truncation: Truncation) -> Self
+    {
+        self.truncation = truncation;
+        self
+    }
+
+    pub fn get_result(&self) -> EliminationResult
+    {
+        eliminate(&self.election, self.truncation, |scores| {
+            let total: Rational = scores.iter().map(|&(_, score)| score).sum();
+            let average = total / Rational::from(scores.len());
+
+            scores.iter().filter(|&&(_, score)| score <= average).map(|&(ref c, _)| c.clone()).collect()
+        })
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+/**
+Baldwin's method repeats the Borda count, each round eliminating the candidate with the fewest points,
+until one candidate is left. It always elects the Condorcet winner.
+Candidates tied for the fewest points are eliminated together, unless that would eliminate everyone,
+in which case they tie.
+*/
+pub struct Baldwin
+{
+    election: Election,
+    truncation: Truncation
+}
+
+impl Baldwin
+{
+    pub fn with_election(election: &Election) -> Self
+    {
+        Baldwin{ election: election.clone(), truncation: Truncation::Average }
+    }
+
+    /// Sets how truncated ballots are counted (Truncation::Average by default).
+    pub fn truncation(mut self, truncation: Truncation) -> Self
+    {
+        self.truncation = truncation;
+        self
+    }
+
+    pub fn get_result(&self) -> EliminationResult
+    {
+        eliminate(&self.election, self.truncation, |scores| {
+            let lowest = scores.iter().map(|&(_, score)| score).min().unwrap_or_default();
+
+            scores.iter().filter(|&&(_, score)| score == lowest).map(|&(ref c, _)| c.clone()).collect()
+        })
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+/// Recounts the Borda scores of the remaining candidates and eliminates whoever 'losers' picks,
+/// until one is left or 'losers' would eliminate them all.
+fn eliminate<F>(election: &Election, truncation: Truncation, losers: F) -> EliminationResult
+    where F: Fn(&[(String, Rational)]) -> Vec<String>
+{
+    let mut remaining = election.candidates();
+    let mut rounds = Vec::new();
+
+    while remaining.len() > 1
+    {
+        let scores = borda_scores(election, &remaining, truncation);
+        let eliminated = losers(&scores);
+
+        if eliminated.len() == remaining.len()
+        {
+            rounds.push(Round::new(scores, Vec::new()));
+            break;
+        }
+
+        remaining.retain(|c| !eliminated.contains(c));
+        rounds.push(Round::new(scores, eliminated));
+    }
+
+    EliminationResult::new(remaining, rounds)
+}
+
+
+#[cfg(test)]
+mod borda_tests
+{
+    use super::*;
+    use fixtures;
+
+    #[test]
+    fn borda()
+    {
+        let result = Borda::with_election(&fixtures::tennessee()).get_result();
+
+        assert_eq!(result.winners, vec!["Nashville"]);
+        assert_eq!(result.ranking, vec![vec!["Nashville"], vec!["Chattanooga"], vec!["Memphis"], vec!["Knoxville"]]);
+        assert!(result.scores.contains(&("Nashville".to_owned(), 194.into())));
+    }
+
+    #[test]
+    fn equal_and_truncated()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        2:A=B>C
+        1:C").unwrap();
+
+        let candidates = election.candidates();
+
+        // A=B share 2 + 1 points; with C alone, A and B share the 0 + 1 points left.
+        assert_eq!(borda_scores(&election, &candidates, Truncation::Average), vec![
+            ("A".to_owned(), Rational::new(7, 2)),
+            ("B".to_owned(), Rational::new(7, 2)),
+            ("C".to_owned(), 2.into())
+        ]);
+        assert_eq!(borda_scores(&election, &candidates, Truncation::Zero), vec![
+            ("A".to_owned(), 3.into()),
+            ("B".to_owned(), 3.into()),
+            ("C".to_owned(), 2.into())
+        ]);
+    }
+
+    #[test]
+    fn pairwise()
+    {
+        let election = fixtures::tennessee();
+        let candidates = election.candidates();
+        let sum_matrix = SumMatrix::new(&election);
+
+        // On complete ballots, a candidate's points are their pairwise wins.
+        for (cand, score) in borda_scores(&election, &candidates, Truncation::Average)
+        {
+            let wins: Rational = candidates.iter().filter(|&c| *c != cand).map(|c| sum_matrix.wins(&cand, c)).sum();
+            assert_eq!(score, wins);
+        }
+    }
+
+    #[test]
+    fn nanson()
+    {
+        let result = Nanson::with_election(&fixtures::tennessee()).get_result();
+
+        // Average 150: Memphis (126) and Knoxville (107) go first, then Chattanooga loses 32 to 68.
+        assert_eq!(result.winners, vec!["Nashville"]);
+        assert_eq!(result.rounds.len(), 2);
+        assert_eq!(result.rounds[0].tally("Memphis"), Some(126.into()));
+        assert_eq!(result.rounds[0].tally("Knoxville"), Some(107.into()));
+        assert_eq!(result.rounds[0].eliminated, vec!["Knoxville", "Memphis"]);
+        assert_eq!(result.rounds[1].tally("Chattanooga"), Some(32.into()));
+        assert_eq!(result.ranking, vec![vec!["Nashville"], vec!["Chattanooga"], vec!["Memphis"], vec!["Knoxville"]]);
+    }
+
+    #[test]
+    fn baldwin()
+    {
+        let result = Baldwin::with_election(&fixtures::tennessee()).get_result();
+
+        assert_eq!(result.winners, vec!["Nashville"]);
+        assert_eq!(result.rounds.iter().map(|r| r.eliminated.clone()).collect::<Vec<_>>(),
+            vec![vec!["Knoxville"], vec!["Memphis"], vec!["Chattanooga"]]);
+    }
+
+    #[test]
+    fn tie()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        1:A>B>C
+        1:B>C>A
+        1:C>A>B").unwrap();
+
+        for result in vec![Nanson::with_election(&election).get_result(), Baldwin::with_election(&election).get_result()]
+        {
+            assert_eq!(result.winners, vec!["A", "B", "C"]);
+            assert_eq!(result.rounds.len(), 1);
+            assert!(result.rounds[0].eliminated.is_empty());
+        }
+    }
+
+    #[test]
+    fn black()
+    {
+        let result = Black::with_election(&fixtures::tennessee()).get_result();
+
+        assert_eq!(result.condorcet_winner, Some("Nashville".to_owned()));
+        assert_eq!(result.winners, vec!["Nashville"]);
+
+        let mut election = Election::new();
+
+        // A beats B, B beats C and C beats A, so the Borda count decides.
+        election.add_ballots("
+        4:A>B>C
+        3:B>C>A
+        2:C>A>B").unwrap();
+
+        let result = Black::with_election(&election).get_result();
+
+        assert_eq!(result.condorcet_winner, None);
+        assert_eq!(result.borda.scores, vec![
+            ("A".to_owned(), 10.into()),
+            ("B".to_owned(), 10.into()),
+            ("C".to_owned(), 7.into())
+        ]);
+        assert_eq!(result.winners, vec!["A", "B"]);
+    }
+}
@@ -0,0 +1,163 @@
+use election::Election;
+use rational::Rational;
+use rounds::{Round, EliminationResult};
+use irv::TieBreak;
+
+
+/**
+Bucklin's method counts first preferences, then adds second preferences, then third, and so on,
+until some candidate is ranked at or above the current level on a majority of the ballots.
+The candidate with the most votes at that level wins.
+
+A level is one rank of a ballot, so candidates ranked equal are all added at the same level,
+each with the ballot's full weight. A truncated ballot adds nothing once its ranks run out,
+but still counts towards the majority; if no one ever reaches a majority, the candidate with
+the most votes once every rank is counted wins.
+*/
+pub struct Bucklin
+{
+    election: Election,
+    tie_break: TieBreak
+}
+
+impl Bucklin
+{
+    pub fn with_election(election: &Election) -> Self
+    {
+        Bucklin{ election: election.clone(), tie_break: TieBreak::Backward }
+    }
+
+    /**
+    Sets how ties for the most votes are broken (TieBreak::Backward by default).
+    Backward elects whoever had the most votes at the latest earlier level where they differed,
+    and Order elects whoever comes first in the order.
+    */
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self
+    {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /**
+    Gets the tallies at each level as rounds, and the winners. No one is eliminated;
+    the ranking is by the tallies at the last level counted.
+    */
+    pub fn get_result(&self) -> EliminationResult
+    {
+        let candidates = self.election.candidates();
+        let levels = self.election.votes().iter().map(|ballot| ballot.ranks.len()).max().unwrap_or(0);
+        let total: Rational = self.election.votes().iter().map(|ballot| ballot.weight).sum();
+        let mut rng = self.tie_break.rng();
+        let mut rounds: Vec<Round> = Vec::new();
+
+        for level in 1..(levels + 1)
+        {
+            let tallies: Vec<(String, Rational)> = candidates.iter()
+                .map(|c| {
+                    let votes = self.election.votes().iter()
+                        .filter(|ballot| ballot.ranks.iter().take(level).any(|rank| rank.contains(c)))
+                        .map(|ballot| ballot.weight)
+                        .sum();
+
+                    (c.clone(), votes)
+                })
+                .collect();
+
+            let most = tallies.iter().map(|&(_, t)| t).max().unwrap_or_default();
+
+            if most * Rational::from(2) > total || level == levels
+            {
+                let tied = tallies.iter().filter(|&&(_, t)| t == most).map(|&(ref c, _)| c.clone()).collect();
+                let winners = self.tie_break.winners(tied, &rounds, &mut rng);
+
+                rounds.push(Round::new(tallies, Vec::new()));
+                return EliminationResult::new(winners, rounds);
+            }
+
+            rounds.push(Round::new(tallies, Vec::new()));
+        }
+
+        EliminationResult::new(Vec::new(), rounds)
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+
+#[cfg(test)]
+mod bucklin_tests
+{
+    use super::*;
+    use fixtures;
+
+    #[test]
+    fn tennessee()
+    {
+        let election = fixtures::tennessee();
+
+        let result = Bucklin::with_election(&election).get_result();
+
+        assert_eq!(result.rounds.len(), 2);
+        assert_eq!(result.rounds[1].tally("Nashville"), Some(68.into()));
+        assert_eq!(result.rounds[1].tally("Chattanooga"), Some(58.into()));
+        assert_eq!(result.winners, vec!["Nashville"]);
+    }
+
+    #[test]
+    fn truncated()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        3:A
+        2:B>C
+        2:C>B
+        2:D>C").unwrap();
+
+        // The ballots for A alone add nothing at the second level, where C reaches 6 of 9.
+        let result = Bucklin::with_election(&election).get_result();
+        assert_eq!(result.rounds[1].tally("A"), Some(3.into()));
+        assert_eq!(result.winners, vec!["C"]);
+        assert_eq!(result.ranking, vec![vec!["C"], vec!["B"], vec!["A"], vec!["D"]]);
+
+        let mut election = Election::new();
+
+        election.add_ballots("
+        3:A
+        2:B
+        2:C").unwrap();
+
+        // No one gets a majority, so the most votes wins.
+        assert_eq!(Bucklin::with_election(&election).get_winners(), vec!["A"]);
+    }
+
+    #[test]
+    fn tie_breaks()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        3:A>C
+        2:B>A
+        2:C>B").unwrap();
+
+        // A and C both reach 5 at the second level, but A had more first preferences.
+        let result = Bucklin::with_election(&election).get_result();
+        assert_eq!(result.rounds[1].tally("C"), Some(5.into()));
+        assert_eq!(result.winners, vec!["A"]);
+
+        let mut election = Election::new();
+
+        election.add_ballots("
+        2:A>B
+        2:B>A").unwrap();
+
+        assert_eq!(Bucklin::with_election(&election).get_winners(), vec!["A", "B"]);
+
+        let order = TieBreak::Order(vec!["B".to_owned(), "A".to_owned()]);
+        assert_eq!(Bucklin::with_election(&election).tie_break(order).get_winners(), vec!["B"]);
+    }
+}
@@ -0,0 +1,292 @@
+//! Importers for results exported by the Condorcet Internet Voting Service (CIVS),
+//! so that its elections can be re-tallied with RankedPairs.
+
+use std::collections::{BTreeMap, HashSet};
+
+use election::{Election, Ballot};
+use rational::Rational;
+use sum_matrix::SumMatrix;
+
+/// Splits a CSV line into fields.
+/// Fields may be quoted, with "" standing for a quote inside a quoted field.
+fn split_csv_line(line: &str) -> Vec<String>
+{
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next()
+    {
+        match c
+        {
+            '"' if quoted && chars.peek() == Some(&'"') => { field.push('"'); chars.next(); },
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(::std::mem::replace(&mut field, String::new())),
+            _ => field.push(c)
+        }
+    }
+
+    fields.push(field);
+
+    fields.into_iter().map(|f| f.trim().to_owned()).collect()
+}
+
+/// The non-blank lines of a file, split into fields, along with their line numbers (from 1).
+fn rows(data: &str) -> Vec<(usize, Vec<String>)>
+{
+    data.lines()
+        .enumerate()
+        .filter(|&(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| (i + 1, split_csv_line(line)))
+        .collect()
+}
+
+/// Reads the header row of candidate names.
+fn header(row: &[String], line: usize) -> Result<Vec<String>, CivsError>
+{
+    let mut seen = HashSet::new();
+
+    for name in row
+    {
+        if name.is_empty()
+        {
+            return Err(CivsError::new(CivsErrorKind::EmptyCandidate, line));
+        }
+        if !seen.insert(name)
+        {
+            return Err(CivsError::new(CivsErrorKind::DuplicateCandidate, line));
+        }
+    }
+
+    Ok(row.to_vec())
+}
+
+/// An unranked cell: CIVS leaves "no opinion" choices blank or marks them with "-".
+fn is_unranked(cell: &str) -> bool
+{
+    cell.is_empty() || cell == "-"
+}
+
+/**
+Reads CIVS's anonymised ballot CSV.
+The first row names the choices; each following row is one ballot with a rank number per choice,
+1 being the most preferred. Equal numbers are equal rankings, and blank or "-" cells are unranked.
+*/
+pub fn read_ballots(data: &str) -> Result<Election, CivsError>
+{
+    let rows = rows(data);
+
+    let (candidates, ballot_rows) = match rows.split_first()
+    {
+        Some((&(line, ref names), rest)) => (header(names, line)?, rest),
+        None => return Err(CivsError::new(CivsErrorKind::Empty, 0))
+    };
+
+    let mut election = Election::new();
+
+    for &(line, ref cells) in ballot_rows
+    {
+        if cells.len() != candidates.len()
+        {
+            return Err(CivsError::new(CivsErrorKind::WrongColumnCount, line));
+        }
+
+        let mut by_rank: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+
+        for (cand, cell) in candidates.iter().zip(cells)
+        {
+            if is_unranked(cell)
+            {
+                continue;
+            }
+
+            match cell.parse::<u32>()
+            {
+                Ok(rank) if rank > 0 => by_rank.entry(rank).or_insert_with(Vec::new).push(cand.clone()),
+                _ => return Err(CivsError::new(CivsErrorKind::InvalidRank, line))
+            }
+        }
+
+        election.ballots.push(Ballot::new(Rational::one(), by_rank.into_iter().map(|(_, rank)| rank).collect()));
+    }
+
+    Ok(election)
+}
+
+/**
+Reads a CIVS pairwise matrix export.
+The first row is a blank cell followed by the choices. Each following row starts with a choice,
+followed by how many voters preferred it over the choice of each column.
+The diagonal may be blank or "-".
+*/
+pub fn read_matrix(data: &str) -> Result<SumMatrix, CivsError>
+{
+    let rows = rows(data);
+
+    let (candidates, matrix_rows) = match rows.split_first()
+    {
+        Some((&(line, ref names), rest)) => {
+            if names.len() < 2 || !names[0].is_empty()
+            {
+                return Err(CivsError::new(CivsErrorKind::WrongColumnCount, line));
+            }
+            (header(&names[1..], line)?, rest)
+        },
+        None => return Err(CivsError::new(CivsErrorKind::Empty, 0))
+    };
+
+    if matrix_rows.len() != candidates.len()
+    {
+        let line = matrix_rows.last().map(|&(line, _)| line).unwrap_or(0);
+        return Err(CivsError::new(CivsErrorKind::WrongRowCount, line));
+    }
+
+    let mut sum_matrix = SumMatrix::empty();
+    let mut seen = HashSet::new();
+
+    for &(line, ref cells) in matrix_rows
+    {
+        if cells.len() != candidates.len() + 1
+        {
+            return Err(CivsError::new(CivsErrorKind::WrongColumnCount, line));
+        }
+
+        let row_cand = &cells[0];
+
+        if !candidates.contains(row_cand)
+        {
+            return Err(CivsError::new(CivsErrorKind::UnknownCandidate, line));
+        }
+        if !seen.insert(row_cand)
+        {
+            return Err(CivsError::new(CivsErrorKind::DuplicateCandidate, line));
+        }
+
+        for (col_cand, cell) in candidates.iter().zip(&cells[1..])
+        {
+            if col_cand == row_cand
+            {
+                if is_unranked(cell) || cell == "0"
+                {
+                    continue;
+                }
+                return Err(CivsError::new(CivsErrorKind::InvalidCount, line));
+            }
+
+            match cell.parse::<Rational>()
+            {
+                Ok(count) if !count.is_negative() => sum_matrix.add_wins(row_cand, col_cand, count),
+                _ => return Err(CivsError::new(CivsErrorKind::InvalidCount, line))
+            }
+        }
+    }
+
+    Ok(sum_matrix)
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CivsErrorKind
+{
+    Empty,
+    EmptyCandidate,
+    DuplicateCandidate,
+    UnknownCandidate,
+    WrongColumnCount,
+    WrongRowCount,
+    InvalidRank,
+    InvalidCount
+}
+
+/// A problem in a CIVS file, and the line (from 1) it was found on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CivsError
+{
+    pub kind: CivsErrorKind,
+    pub line: usize
+}
+
+impl CivsError
+{
+    fn new(kind: CivsErrorKind, line: usize) -> Self
+    {
+        CivsError { kind: kind, line: line }
+    }
+}
+
+use std::error::Error;
+
+impl Error for CivsError
+{
+    fn description(&self) -> &str
+    {
+        match self.kind
+        {
+            CivsErrorKind::Empty => "file has no header",
+            CivsErrorKind::EmptyCandidate => "blank candidate name",
+            CivsErrorKind::DuplicateCandidate => "candidate listed more than once",
+            CivsErrorKind::UnknownCandidate => "candidate not in header",
+            CivsErrorKind::WrongColumnCount => "wrong number of columns",
+            CivsErrorKind::WrongRowCount => "wrong number of rows",
+            CivsErrorKind::InvalidRank => "rank is not a positive whole number",
+            CivsErrorKind::InvalidCount => "invalid voter count"
+        }
+    }
+}
+
+use std::fmt::{self, Formatter, Display};
+
+impl Display for CivsError
+{
+    #[allow(deprecated)]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result
+    {
+        write!(f, "line {}: {}", self.line, self.description())
+    }
+}
+
+
+#[cfg(test)]
+mod civs_tests
+{
+    use super::*;
+    use ranked_pairs::{RankedPairs, StrengthType};
+
+    #[test]
+    fn reads_ballots_and_matrix()
+    {
+        let ballots = "
+\"Memphis\",\"Nashville\",\"Chattanooga\",\"Knoxville\"
+1,2,3,4
+4,1,2,3
+4,3,1,2
+4,3,2,1
+-,1,1,
+";
+        let election = read_ballots(ballots).unwrap();
+
+        assert_eq!(election.votes().len(), 5);
+        assert_eq!(election.votes()[4].ranks, vec![vec!["Nashville".to_owned(), "Chattanooga".to_owned()]]);
+
+        let matrix = "
+,Memphis,Nashville,Chattanooga,Knoxville
+Memphis,-,42,42,42
+Nashville,58,-,68,68
+Chattanooga,58,32,-,83
+Knoxville,58,32,17,-
+";
+        let sum_matrix = read_matrix(matrix).unwrap();
+
+        let winner = RankedPairs::with_matrix(sum_matrix, StrengthType::Margin).get_winner().unwrap();
+        assert_eq!(winner, "Nashville");
+    }
+
+    #[test]
+    fn reports_lines()
+    {
+        assert_eq!(read_ballots("A,B\n1,2\n\n1,x").err(), Some(CivsError::new(CivsErrorKind::InvalidRank, 4)));
+        assert_eq!(read_ballots("A,A\n1,2").err(), Some(CivsError::new(CivsErrorKind::DuplicateCandidate, 1)));
+        assert_eq!(read_matrix(",A,B\nA,-,3").err(), Some(CivsError::new(CivsErrorKind::WrongRowCount, 2)));
+    }
+}
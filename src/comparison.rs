@@ -0,0 +1,288 @@
+use std::fmt::{self, Formatter, Display};
+
+use election::Election;
+use sum_matrix::SumMatrix;
+use method::{Input, Options, Outcome, MethodError};
+use registry::Registry;
+
+
+/**
+Runs every method in a registry on one election, side by side, to check that they agree.
+Where they don't, each method's result is checked against the Condorcet winner and the Smith set,
+which explain most disagreements: methods that aren't Condorcet-consistent can pass over a Condorcet winner,
+and without one, Condorcet methods can pick different members of the Smith set.
+Display writes it as a table.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison
+{
+    /// One row per method, in the registry's (alphabetical) order.
+    pub rows: Vec<Row>,
+    pub condorcet_winner: Option<String>,
+    pub smith_set: Vec<String>,
+    /// The winners most methods agree on (the first alphabetically, if several are as common).
+    pub consensus: Vec<String>
+}
+
+/// One method's result in a Comparison.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row
+{
+    pub method: String,
+    pub outcome: Result<Outcome, MethodError>,
+    /// What sets this method's winners apart, if anything.
+    pub notes: Vec<Note>
+}
+
+impl Row
+{
+    /// Whether the method ran, and elected someone other than the consensus.
+    pub fn disagrees(&self, consensus: &[String]) -> bool
+    {
+        match self.outcome
+        {
+            Ok(ref outcome) => outcome.winners != consensus,
+            Err(_) => false
+        }
+    }
+}
+
+/// A criterion a method's winners fall foul of in this election.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Note
+{
+    /// There's a Condorcet winner, and the method doesn't elect them (alone).
+    MissesCondorcetWinner(String),
+    /// The method elects these candidates from outside the Smith set.
+    OutsideSmithSet(Vec<String>),
+    /// The method ties between several winners.
+    Tie
+}
+
+impl Display for Note
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result
+    {
+        match *self
+        {
+            Note::MissesCondorcetWinner(ref winner) => write!(f, "doesn't elect the Condorcet winner, {}", winner),
+            Note::OutsideSmithSet(ref outside) => write!(f, "elects {} from outside the Smith set", outside.join(", ")),
+            Note::Tie => write!(f, "ties")
+        }
+    }
+}
+
+impl Comparison
+{
+    /// Runs every method in 'registry' on 'election' with the same options.
+    pub fn new(registry: &Registry, election: &Election, options: &Options) -> Self
+    {
+        let sum_matrix = SumMatrix::new(election);
+        let condorcet_winner = sum_matrix.condorcet_winner();
+        let smith_set = sum_matrix.smith_set();
+
+        let rows: Vec<Row> = registry.names().into_iter()
+            .map(|name| {
+                let outcome = registry.run(name, &Input::Election(election), options);
+                let notes = match outcome
+                {
+                    Ok(ref outcome) => notes(&outcome.winners, &condorcet_winner, &smith_set),
+                    Err(_) => Vec::new()
+                };
+
+                Row { method: name.to_owned(), outcome: outcome, notes: notes }
+            })
+            .collect();
+
+        // The most common winners, as (count, winners).
+        let mut counts: Vec<(usize, Vec<String>)> = Vec::new();
+
+        for outcome in rows.iter().filter_map(|row| row.outcome.as_ref().ok())
+        {
+            match counts.iter().position(|&(_, ref winners)| *winners == outcome.winners)
+            {
+                Some(i) => counts[i].0 += 1,
+                None => counts.push((1, outcome.winners.clone()))
+            }
+        }
+
+        counts.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        let consensus = counts.into_iter().next().map(|(_, winners)| winners).unwrap_or_default();
+
+        Comparison { rows: rows, condorcet_winner: condorcet_winner, smith_set: smith_set, consensus: consensus }
+    }
+
+    /// Whether every method that ran elected the same winners.
+    pub fn unanimous(&self) -> bool
+    {
+        self.disagreements().is_empty()
+    }
+
+    /// The methods that ran and elected someone other than the consensus.
+    pub fn disagreements(&self) -> Vec<&Row>
+    {
+        self.rows.iter().filter(|row| row.disagrees(&self.consensus)).collect()
+    }
+
+    /// The methods that couldn't give a result, such as those that need ballots or limit the candidates.
+    pub fn failures(&self) -> Vec<&Row>
+    {
+        self.rows.iter().filter(|row| row.outcome.is_err()).collect()
+    }
+}
+
+fn notes(winners: &[String], condorcet_winner: &Option<String>, smith_set: &[String]) -> Vec<Note>
+{
+    let mut notes = Vec::new();
+
+    if let Some(ref condorcet_winner) = *condorcet_winner
+    {
+        if winners != [condorcet_winner.clone()]
+        {
+            notes.push(Note::MissesCondorcetWinner(condorcet_winner.clone()));
+        }
+    }
+
+    let outside: Vec<String> = winners.iter().filter(|&c| !smith_set.contains(c)).cloned().collect();
+
+    if !outside.is_empty()
+    {
+        notes.push(Note::OutsideSmithSet(outside));
+    }
+
+    if winners.len() > 1
+    {
+        notes.push(Note::Tie);
+    }
+
+    notes
+}
+
+/// Writes ranks best first, separated by '>', with candidates sharing a rank separated by '='.
+fn ranking_text(ranking: &[Vec<String>]) -> String
+{
+    ranking.iter().map(|rank| rank.join(" = ")).collect::<Vec<_>>().join(" > ")
+}
+
+/**
+Writes the criteria, then a table with a row per method: its winners and ranking,
+marked with '*' if it disagrees with the consensus, or its error if it failed.
+Then each disagreement with the notes explaining it.
+*/
+impl Display for Comparison
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result
+    {
+        match self.condorcet_winner
+        {
+            Some(ref winner) => writeln!(f, "Condorcet winner: {}", winner)?,
+            None => writeln!(f, "Condorcet winner: none")?
+        }
+
+        writeln!(f, "Smith set ({}): {}", self.smith_set.len(), self.smith_set.join(", "))?;
+        writeln!(f)?;
+
+        let winners: Vec<String> = self.rows.iter()
+            .map(|row| match row.outcome
+            {
+                Ok(ref outcome) => outcome.winners.join(", "),
+                Err(_) => "-".to_owned()
+            })
+            .collect();
+
+        let method_width = self.rows.iter().map(|row| row.method.len()).chain(Some("Method".len())).max().unwrap_or(0);
+        let winners_width = winners.iter().map(|w| w.len()).chain(Some("Winners".len())).max().unwrap_or(0);
+
+        writeln!(f, "  {:mw$}  {:ww$}  Ranking", "Method", "Winners", mw = method_width, ww = winners_width)?;
+
+        for (row, winners) in self.rows.iter().zip(&winners)
+        {
+            let mark = if row.disagrees(&self.consensus) { "*" } else { " " };
+            let ranking = match row.outcome
+            {
+                Ok(ref outcome) => ranking_text(&outcome.ranking),
+                Err(ref e) => format!("failed: {}", e)
+            };
+
+            writeln!(f, "{} {:mw$}  {:ww$}  {}", mark, row.method, winners, ranking, mw = method_width, ww = winners_width)?;
+        }
+
+        let disagreements = self.disagreements();
+
+        writeln!(f)?;
+
+        if disagreements.is_empty()
+        {
+            writeln!(f, "Every method elects {}", self.consensus.join(", "))?;
+        }
+        else {
+            writeln!(f, "Most methods elect {}; {} of {} disagree:", self.consensus.join(", "), disagreements.len(), self.rows.len())?;
+
+            for row in disagreements
+            {
+                let notes: Vec<String> = row.notes.iter().map(|note| note.to_string()).collect();
+
+                if notes.is_empty()
+                {
+                    writeln!(f, "  {}", row.method)?;
+                }
+                else {
+                    writeln!(f, "  {}: {}", row.method, notes.join("; "))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod comparison_tests
+{
+    use super::*;
+    use fixtures;
+
+    #[test]
+    fn tennessee_irv()
+    {
+        let comparison = Comparison::new(&Registry::default(), &fixtures::tennessee(), &Options::new());
+
+        assert_eq!(comparison.condorcet_winner, Some("Nashville".to_owned()));
+        assert_eq!(comparison.smith_set, vec!["Nashville"]);
+        assert_eq!(comparison.consensus, vec!["Nashville"]);
+        assert_eq!(comparison.rows.len(), Registry::default().names().len());
+        assert!(comparison.failures().is_empty());
+
+        // Only IRV passes over the Condorcet winner.
+        let disagreements = comparison.disagreements();
+        assert_eq!(disagreements.len(), 1);
+        assert_eq!(disagreements[0].method, "irv");
+        assert_eq!(disagreements[0].notes, vec![
+            Note::MissesCondorcetWinner("Nashville".to_owned()),
+            Note::OutsideSmithSet(vec!["Knoxville".to_owned()])
+        ]);
+
+        let text = comparison.to_string();
+        assert!(text.starts_with("Condorcet winner: Nashville\nSmith set (1): Nashville\n"));
+        assert!(text.contains("* irv "));
+        assert!(text.contains("Knoxville > Memphis > Nashville > Chattanooga"));
+        assert!(text.contains("irv: doesn't elect the Condorcet winner, Nashville; elects Knoxville from outside the Smith set"));
+    }
+
+    #[test]
+    fn unanimous()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        3:A>B>C
+        1:B>A>C").unwrap();
+
+        // A is the first choice of a majority, and every method agrees.
+        let comparison = Comparison::new(&Registry::default(), &election, &Options::new());
+
+        assert!(comparison.unanimous());
+        assert!(comparison.to_string().ends_with("Every method elects A\n"));
+    }
+}
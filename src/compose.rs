@@ -0,0 +1,212 @@
+use method::{VotingMethod, Input, Options, Outcome, MethodError};
+use dominance::DominantSet;
+
+
+/// What a Composed method narrows the candidates to before the other method runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter
+{
+    /// The Smith or Schwartz set of the candidates left.
+    Set(DominantSet),
+    /// The Condorcet winner of the candidates left, if there is one; otherwise all of them.
+    CondorcetWinner
+}
+
+impl Filter
+{
+    /// The prefix of a composed method's name, as in "smith//irv".
+    pub fn prefix(&self) -> &'static str
+    {
+        match *self
+        {
+            Filter::Set(DominantSet::Smith) => "smith",
+            Filter::Set(DominantSet::Schwartz) => "schwartz",
+            Filter::CondorcetWinner => "condorcet"
+        }
+    }
+
+    pub fn from_prefix(prefix: &str) -> Option<Self>
+    {
+        match prefix
+        {
+            "smith" => Some(Filter::Set(DominantSet::Smith)),
+            "schwartz" => Some(Filter::Set(DominantSet::Schwartz)),
+            "condorcet" => Some(Filter::CondorcetWinner),
+            _ => None
+        }
+    }
+}
+
+/**
+Composes a filter with any other method: Smith//X runs X on the Smith set, Schwartz//X on the Schwartz set,
+and Condorcet//X elects the Condorcet winner if there is one and runs X otherwise.
+The other method sees an Input restricted to the filtered candidates, so it works with every method.
+
+The full ranking repeats this on the candidates left: for Smith//X, X ranks the Smith set,
+then the Smith set of everyone else, and so on. Condorcet//X places Condorcet winners one at a time
+until there isn't one, and X ranks the rest.
+*/
+pub struct Composed<'a>
+{
+    name: String,
+    filter: Filter,
+    method: Box<dyn VotingMethod + 'a>
+}
+
+impl<'a> Composed<'a>
+{
+    pub fn new(filter: Filter, method: Box<dyn VotingMethod + 'a>) -> Self
+    {
+        Composed { name: format!("{}//{}", filter.prefix(), method.name()), filter: filter, method: method }
+    }
+
+    /// Smith//X.
+    pub fn smith(method: Box<dyn VotingMethod + 'a>) -> Self
+    {
+        Composed::new(Filter::Set(DominantSet::Smith), method)
+    }
+
+    /// Schwartz//X.
+    pub fn schwartz(method: Box<dyn VotingMethod + 'a>) -> Self
+    {
+        Composed::new(Filter::Set(DominantSet::Schwartz), method)
+    }
+
+    /// Condorcet//X.
+    pub fn condorcet(method: Box<dyn VotingMethod + 'a>) -> Self
+    {
+        Composed::new(Filter::CondorcetWinner, method)
+    }
+}
+
+impl<'a> VotingMethod for Composed<'a>
+{
+    fn name(&self) -> &str
+    {
+        &self.name
+    }
+
+    fn needs_ballots(&self) -> bool
+    {
+        self.method.needs_ballots()
+    }
+
+    fn run(&self, input: &Input, options: &Options) -> Result<Outcome, MethodError>
+    {
+        let mut remaining = input.candidates();
+        let mut ranking: Vec<Vec<String>> = Vec::new();
+        let mut winners: Option<Vec<String>> = None;
+        let mut explanation = Vec::new();
+
+        while !remaining.is_empty()
+        {
+            let sum_matrix = input.restricted(&remaining).input().sum_matrix();
+
+            let (layer, placed) = match self.filter
+            {
+                Filter::Set(set) => {
+                    let layer = set.of(&sum_matrix);
+                    explanation.push(format!("{} set: {}", self.filter.prefix(), layer.join(", ")));
+
+                    let outcome = self.method.run(&input.restricted(&layer).input(), options)?;
+                    (layer, outcome)
+                },
+                Filter::CondorcetWinner => match sum_matrix.condorcet_winner()
+                {
+                    Some(winner) => {
+                        explanation.push(format!("Condorcet winner: {}", winner));
+
+                        (vec![winner.clone()], Outcome::from_ranking(vec![vec![winner]], Vec::new()))
+                    },
+                    None => {
+                        explanation.push(format!("No Condorcet winner among {}", remaining.join(", ")));
+
+                        let outcome = self.method.run(&input.restricted(&remaining).input(), options)?;
+                        (remaining.clone(), outcome)
+                    }
+                }
+            };
+
+            if layer.is_empty()
+            {
+                break;
+            }
+
+            explanation.extend(placed.explanation);
+            winners = winners.or(Some(placed.winners));
+            ranking.extend(placed.ranking);
+            remaining.retain(|c| !layer.contains(c));
+        }
+
+        Ok(Outcome { winners: winners.unwrap_or_default(), ranking: ranking, explanation: explanation })
+    }
+}
+
+
+#[cfg(test)]
+mod compose_tests
+{
+    use super::*;
+    use fixtures;
+    use registry::Registry;
+    use irv::SmithIrv;
+
+    #[test]
+    fn smith()
+    {
+        let election = fixtures::hybrids();
+        let registry = Registry::default();
+        let smith_irv = Composed::smith(Box::new(registry.get("irv").unwrap()));
+
+        assert_eq!(smith_irv.name(), "smith//irv");
+        assert!(smith_irv.needs_ballots());
+
+        let outcome = smith_irv.run(&Input::Election(&election), &Options::new()).unwrap();
+
+        // The same as the hard-coded hybrid, and A comes last, after the Smith set.
+        assert_eq!(outcome.winners, SmithIrv::with_election(&election).get_winners());
+        assert_eq!(outcome.winners, vec!["D"]);
+        assert_eq!(outcome.ranking.len(), 4);
+        assert_eq!(outcome.ranking[3], vec!["A"]);
+    }
+
+    #[test]
+    fn condorcet()
+    {
+        let election = fixtures::tennessee();
+
+        let registry = Registry::default();
+        let condorcet_irv = Composed::condorcet(Box::new(registry.get("irv").unwrap()));
+        let outcome = condorcet_irv.run(&Input::Election(&election), &Options::new()).unwrap();
+
+        // IRV alone elects Knoxville, but Nashville, then Chattanooga and Knoxville are Condorcet winners in turn.
+        assert_eq!(outcome.winners, vec!["Nashville"]);
+        assert_eq!(outcome.ranking, vec![vec!["Nashville"], vec!["Chattanooga"], vec!["Knoxville"], vec!["Memphis"]]);
+
+        // Without a Condorcet winner, IRV decides.
+        let election = fixtures::hybrids();
+        let outcome = condorcet_irv.run(&Input::Election(&election), &Options::new()).unwrap();
+        assert_eq!(outcome.winners, vec!["C"]);
+    }
+
+    #[test]
+    fn matrix()
+    {
+        let election = fixtures::hybrids();
+        let sum_matrix = ::sum_matrix::SumMatrix::new(&election);
+        let registry = Registry::default();
+
+        // A loses every matchup, but only by 3, so minimax elects them; restricted to the Schwartz set, B's defeat is smallest.
+        let minimax = registry.run("minimax", &Input::Matrix(&sum_matrix), &Options::new()).unwrap();
+        assert_eq!(minimax.winners, vec!["A"]);
+
+        let schwartz_minimax = Composed::schwartz(Box::new(registry.get("minimax").unwrap()));
+        let outcome = schwartz_minimax.run(&Input::Matrix(&sum_matrix), &Options::new()).unwrap();
+        assert_eq!(outcome.winners, vec!["B"]);
+        assert_eq!(outcome.ranking.last(), Some(&vec!["A".to_owned()]));
+
+        let smith_borda = Composed::smith(Box::new(registry.get("borda").unwrap()));
+        assert_eq!(smith_borda.run(&Input::Matrix(&sum_matrix), &Options::new()),
+            Err(MethodError::NeedsBallots("borda".to_owned())));
+    }
+}
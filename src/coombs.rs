@@ -0,0 +1,224 @@
+use election::{Election, Ballot};
+use rational::Rational;
+use rounds::{Round, EliminationResult};
+use irv::{TieBreak, first_preferences, transfers};
+
+
+/**
+How a ballot's weight is split for last place among the 'continuing' candidates.
+Candidates a ballot leaves unranked share its last place; if it ranks them all, those in its lowest rank
+that has any of them share it. Empty if it ranks none of them, since such a ballot is exhausted.
+*/
+fn last_place(ballot: &Ballot, continuing: &[String]) -> Vec<(usize, Rational)>
+{
+    let ranked = |c: &String| ballot.ranks.iter().any(|rank| rank.contains(c));
+
+    if continuing.iter().all(|c| !ranked(c))
+    {
+        return Vec::new();
+    }
+
+    let mut bottom: Vec<usize> = (0..continuing.len()).filter(|&i| !ranked(&continuing[i])).collect();
+
+    if bottom.is_empty()
+    {
+        bottom = ballot.ranks.iter().rev()
+            .map(|rank| (0..continuing.len()).filter(|&i| rank.contains(&continuing[i])).collect::<Vec<usize>>())
+            .find(|rank| !rank.is_empty())
+            .unwrap_or_default();
+    }
+
+    let share = ballot.weight / Rational::from(bottom.len());
+    bottom.into_iter().map(|i| (i, share)).collect()
+}
+
+/// Counts each continuing candidate's last places.
+pub fn last_places(election: &Election, continuing: &[String]) -> Vec<(String, Rational)>
+{
+    let mut tallies: Vec<(String, Rational)> = continuing.iter().map(|c| (c.clone(), Rational::zero())).collect();
+
+    for ballot in election.votes()
+    {
+        for (i, share) in last_place(ballot, continuing)
+        {
+            tallies[i].1 += share;
+        }
+    }
+
+    tallies
+}
+
+/// The outcome of a Coombs count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoombsResult
+{
+    /// Each round's first preferences, exhausted ballots and transfers as in IRV, and the winners and ranking.
+    pub count: EliminationResult,
+    /// Each round's last places, and who was eliminated for having the most.
+    pub last_places: Vec<Round>
+}
+
+/**
+Coombs' method is an elimination count like IRV, but it eliminates the candidate ranked last by the most voters,
+rather than the one ranked first by the fewest. As in IRV, a candidate ranked first on a majority of the ballots
+that aren't exhausted wins.
+
+Truncated ballots put the candidates they leave out last, splitting their last place evenly between them,
+and equal rankings split first and last places the same way. A ballot that ranks none of the candidates left
+is exhausted and counts for neither.
+*/
+pub struct Coombs
+{
+    election: Election,
+    tie_break: TieBreak
+}
+
+impl Coombs
+{
+    pub fn with_election(election: &Election) -> Self
+    {
+        Coombs{ election: election.clone(), tie_break: TieBreak::Backward }
+    }
+
+    /**
+    Sets how ties for the most last places are broken (TieBreak::Backward by default).
+    Backward eliminates whoever had the most last places in the latest earlier round where they differed,
+    and Order eliminates whoever comes last in the order.
+    */
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self
+    {
+        self.tie_break = tie_break;
+        self
+    }
+
+    pub fn get_result(&self) -> CoombsResult
+    {
+        let mut rng = self.tie_break.rng();
+        let mut remaining = self.election.candidates();
+        let mut rounds: Vec<Round> = Vec::new();
+        let mut last_rounds: Vec<Round> = Vec::new();
+
+        while !remaining.is_empty()
+        {
+            let (tallies, exhausted) = first_preferences(&self.election, &remaining);
+            let last = last_places(&self.election, &remaining);
+            let active: Rational = tallies.iter().map(|&(_, t)| t).sum();
+
+            let majority: Vec<String> = tallies.iter()
+                .filter(|&&(_, t)| t * Rational::from(2) > active)
+                .map(|&(ref c, _)| c.clone())
+                .collect();
+
+            let mut round = Round::new(tallies, Vec::new());
+            round.exhausted = exhausted;
+            let mut last_round = Round::new(last.clone(), Vec::new());
+
+            let winners = if remaining.len() == 1 || active.is_zero() { remaining.clone() } else { majority };
+
+            if !winners.is_empty()
+            {
+                rounds.push(round);
+                last_rounds.push(last_round);
+                return self.result(winners, rounds, last_rounds);
+            }
+
+            // TieBreak looks for the lowest tallies, so it's given the last places negated.
+            let history: Vec<Round> = last_rounds.iter()
+                .map(|r| Round::new(r.tallies.iter().map(|&(ref c, t)| (c.clone(), -t)).collect(), Vec::new()))
+                .collect();
+
+            let most = last.iter().map(|&(_, t)| t).max().unwrap_or_default();
+            let tied = last.iter().filter(|&&(_, t)| t == most).map(|&(ref c, _)| c.clone()).collect();
+            let losers = self.tie_break.losers(tied, &history, &mut rng);
+
+            if remaining.iter().all(|c| losers.contains(c))
+            {
+                rounds.push(round);
+                last_rounds.push(last_round);
+                return self.result(remaining, rounds, last_rounds);
+            }
+
+            let next: Vec<String> = remaining.iter().filter(|&c| !losers.contains(c)).cloned().collect();
+
+            round.transfers = transfers(&self.election, &remaining, &next);
+            round.eliminated = losers.clone();
+            last_round.eliminated = losers;
+
+            rounds.push(round);
+            last_rounds.push(last_round);
+            remaining = next;
+        }
+
+        self.result(Vec::new(), rounds, last_rounds)
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().count.winners
+    }
+
+    fn result(&self, winners: Vec<String>, rounds: Vec<Round>, last_places: Vec<Round>) -> CoombsResult
+    {
+        CoombsResult { count: EliminationResult::new(winners, rounds), last_places: last_places }
+    }
+}
+
+
+#[cfg(test)]
+mod coombs_tests
+{
+    use super::*;
+    use fixtures;
+
+    #[test]
+    fn tennessee()
+    {
+        let election = fixtures::tennessee();
+
+        let result = Coombs::with_election(&election).get_result();
+
+        assert_eq!(result.last_places[0].tally("Memphis"), Some(58.into()));
+        assert_eq!(result.last_places[0].eliminated, vec!["Memphis"]);
+        assert_eq!(result.count.rounds[1].tally("Nashville"), Some(68.into()));
+        assert_eq!(result.count.winners, vec!["Nashville"]);
+    }
+
+    #[test]
+    fn truncated()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        4:A>B>C
+        3:B
+        2:C>B>A").unwrap();
+
+        let result = Coombs::with_election(&election).get_result();
+
+        // The ballots for B alone put A and C last, half each.
+        assert_eq!(result.last_places[0].tally("A"), Some(Rational::new(7, 2)));
+        assert_eq!(result.last_places[0].tally("C"), Some(Rational::new(11, 2)));
+        assert_eq!(result.last_places[0].tally("B"), Some(0.into()));
+        assert_eq!(result.count.rounds[0].eliminated, vec!["C"]);
+        assert_eq!(result.count.winners, vec!["B"]);
+    }
+
+    #[test]
+    fn tie_breaks()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        1:A>B>C
+        1:B>C>A
+        1:C>A>B").unwrap();
+
+        assert_eq!(Coombs::with_election(&election).tie_break(TieBreak::All).get_winners(), vec!["A", "B", "C"]);
+
+        // C comes last in the order, and then A is first on two of the three ballots.
+        let order = TieBreak::Order(vec!["A".to_owned(), "B".to_owned(), "C".to_owned()]);
+        let result = Coombs::with_election(&election).tie_break(order).get_result();
+        assert_eq!(result.last_places[0].eliminated, vec!["C"]);
+        assert_eq!(result.count.winners, vec!["A"]);
+    }
+}
@@ -0,0 +1,173 @@
+use sum_matrix::SumMatrix;
+use election::Election;
+use rational::Rational;
+use scores::rank_by;
+
+
+/**
+Copeland scores each candidate by their pairwise record: 1 for each win, 0 for each loss and alpha for each tie.
+The candidates with the best record win. Common choices of alpha are 0, 1/2 and 1.
+Optionally, candidates tied on score are separated by the second-order Copeland score:
+the total score of the opponents they beat (plus alpha times the score of those they tie with).
+*/
+pub struct Copeland
+{
+    sum_matrix: SumMatrix,
+    alpha: Rational,
+    second_order: bool
+}
+
+impl Copeland
+{
+    pub fn with_matrix(sum_matrix: SumMatrix, alpha: Rational) -> Self
+    {
+        Copeland{ sum_matrix: sum_matrix, alpha: alpha, second_order: false }
+    }
+
+    pub fn with_election(election: &Election, alpha: Rational) -> Self
+    {
+        Copeland::with_matrix(SumMatrix::new(election), alpha)
+    }
+
+    /// Turns second-order Copeland tie-breaking on or off.
+    pub fn second_order(mut self, second_order: bool) -> Self
+    {
+        self.second_order = second_order;
+        self
+    }
+
+    /// The points 'for_votes' to 'against_votes' is worth.
+    fn points(&self, for_votes: Rational, against_votes: Rational) -> Rational
+    {
+        if for_votes > against_votes
+        {
+            Rational::one()
+        }
+        else if for_votes == against_votes
+        {
+            self.alpha
+        }
+        else {
+            Rational::zero()
+        }
+    }
+
+    /**
+    Gets the Copeland scores, the ranking, and the candidates tied on score.
+    */
+    pub fn get_result(&self) -> CopelandResult
+    {
+        let candidates = self.sum_matrix.candidates();
+        let counts = self.sum_matrix.counts(&candidates);
+        let n = candidates.len();
+
+        let points: Vec<Vec<Rational>> = (0..n)
+            .map(|i| (0..n).map(|j| if i == j { Rational::zero() } else { self.points(counts[i][j], counts[j][i]) }).collect())
+            .collect();
+
+        let scores: Vec<Rational> = points.iter().map(|row| row.iter().sum()).collect();
+
+        let ties: Vec<Vec<String>> = rank_by(candidates.iter().cloned().zip(scores.iter().cloned()).collect())
+            .into_iter()
+            .filter(|rank| rank.len() > 1)
+            .collect();
+
+        let second_order_scores: Option<Vec<Rational>> = if self.second_order
+        {
+            Some((0..n).map(|i| (0..n).map(|j| points[i][j] * scores[j]).sum()).collect())
+        }
+        else {
+            None
+        };
+
+        let ranking = match second_order_scores
+        {
+            Some(ref second) => rank_by(candidates.iter().cloned().zip(scores.iter().cloned().zip(second.iter().cloned())).collect()),
+            None => rank_by(candidates.iter().cloned().zip(scores.iter().cloned()).collect())
+        };
+
+        let winners = ranking.first().cloned().unwrap_or_default();
+
+        CopelandResult
+        {
+            scores: candidates.iter().cloned().zip(scores).collect(),
+            second_order_scores: second_order_scores.map(|second| candidates.iter().cloned().zip(second).collect()),
+            ranking: ranking,
+            winners: winners,
+            ties: ties
+        }
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+/// The outcome of a Copeland count.
+pub struct CopelandResult
+{
+    /// Each candidate's Copeland score, by candidate name.
+    pub scores: Vec<(String, Rational)>,
+    /// Each candidate's second-order score, if tie-breaking was on.
+    pub second_order_scores: Option<Vec<(String, Rational)>>,
+    /// Best first. With tie-breaking on, only candidates tied on both scores share a rank.
+    pub ranking: Vec<Vec<String>>,
+    pub winners: Vec<String>,
+    /// Groups of candidates with the same Copeland score, before any tie-breaking.
+    pub ties: Vec<Vec<String>>
+}
+
+
+#[cfg(test)]
+mod copeland_tests
+{
+    use super::*;
+
+    fn example() -> Election
+    {
+        let mut election = Election::new();
+
+        // A beats B, B beats C, C beats A, and all three beat D. D ties with E.
+        election.add_ballots("
+        1:A>B>C>D=E
+        1:B>C>A>D=E
+        1:C>A>B>D=E
+        1:D>E
+        1:E>D").unwrap();
+
+        election
+    }
+
+    #[test]
+    fn alpha()
+    {
+        let result = Copeland::with_election(&example(), Rational::new(1, 2)).get_result();
+
+        assert_eq!(result.scores[3], ("D".to_owned(), Rational::new(1, 2)));
+        assert_eq!(result.ranking, vec![vec!["A", "B", "C"], vec!["D", "E"]]);
+        assert_eq!(result.ties, vec![vec!["A", "B", "C"], vec!["D", "E"]]);
+
+        let result = Copeland::with_election(&example(), Rational::zero()).get_result();
+
+        assert_eq!(result.scores[3], ("D".to_owned(), Rational::zero()));
+    }
+
+    #[test]
+    fn second_order()
+    {
+        let mut election = Election::new();
+
+        // A and B both win twice, but A beats B (2 wins) while B beats C (1 win).
+        election.add_ballots("
+        1:A>B>D>C
+        1:B>D>C>A
+        1:C>A>B>D").unwrap();
+
+        let result = Copeland::with_election(&election, Rational::new(1, 2)).second_order(true).get_result();
+
+        assert_eq!(result.ties, vec![vec!["A", "B"], vec!["C", "D"]]);
+        assert_eq!(result.second_order_scores.unwrap()[0], ("A".to_owned(), Rational::from(3)));
+        assert_eq!(result.ranking, vec![vec!["A"], vec!["B"], vec!["C"], vec!["D"]]);
+    }
+}
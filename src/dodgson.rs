@@ -0,0 +1,506 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use election::{Election, Ballot};
+use sum_matrix::SumMatrix;
+use rational::Rational;
+use scores::rank_by;
+
+
+/// The most search states Dodgson and Young try per candidate before falling back to an approximation.
+pub const DEFAULT_MAX_STATES: usize = 1_000_000;
+
+/// Where a ballot ranks a candidate: the index of their rank, with unranked candidates equal last.
+fn position(ballot: &Ballot, cand: &str) -> usize
+{
+    ballot.ranks.iter().position(|rank| rank.iter().any(|c| c == cand)).unwrap_or(ballot.ranks.len())
+}
+
+/// Each candidate's margin against 'cand': how many more voters prefer 'cand' to them than the reverse.
+fn margins(sum_matrix: &SumMatrix, cand: &str, opponents: &[String]) -> Vec<Rational>
+{
+    opponents.iter().map(|d| sum_matrix.wins(cand, d) - sum_matrix.wins(d, cand)).collect()
+}
+
+fn all_integer(election: &Election) -> bool
+{
+    election.votes().iter().all(|b| b.weight.is_integer())
+}
+
+/**
+Dodgson's method elects the candidate closest to being a Condorcet winner, measured in swaps of adjacent
+candidates on the ballots: a candidate's score is the fewest swaps that would make them beat everyone else.
+
+With equal rankings, moving a candidate above one they're ranked equal with counts as one swap, and so does
+moving them above one candidate of the rank above. Unranked candidates count as ranked equal last.
+A candidate passes everyone they're ranked equal with before moving into the rank above.
+
+The exact score is found by a search over the voters, tracking how far short of beating each opponent the candidate
+still is. It needs whole-number ballot weights, and is limited by max_states(). Otherwise every score is
+approximated by a lower bound (the "Dodgson Quick" count): each opponent the candidate doesn't beat needs at least
+half of the shortfall in voters to swap them, at one swap each.
+*/
+pub struct Dodgson
+{
+    election: Election,
+    max_states: usize
+}
+
+impl Dodgson
+{
+    pub fn with_election(election: &Election) -> Self
+    {
+        Dodgson{ election: election.clone(), max_states: DEFAULT_MAX_STATES }
+    }
+
+    /// Limits the exact search (DEFAULT_MAX_STATES by default). Zero always approximates.
+    pub fn max_states(mut self, max_states: usize) -> Self
+    {
+        self.max_states = max_states;
+        self
+    }
+
+    /**
+    Gets each candidate's score (None if they can't become the Condorcet winner), and whether the scores are exact.
+    */
+    pub fn get_result(&self) -> DistanceResult
+    {
+        let candidates = self.election.candidates();
+        let sum_matrix = SumMatrix::new(&self.election);
+
+        let exact = if all_integer(&self.election)
+        {
+            candidates.iter().map(|c| self.exact_score(&sum_matrix, c)).collect::<Option<Vec<_>>>()
+        }
+        else {
+            None
+        };
+
+        match exact
+        {
+            Some(scores) => DistanceResult::new(candidates.into_iter().zip(scores).collect(), true),
+            None => {
+                let scores = candidates.iter().map(|c| self.quick_score(&sum_matrix, c)).collect::<Vec<_>>();
+                DistanceResult::new(candidates.into_iter().zip(scores).collect(), false)
+            }
+        }
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+
+    /// The shortfall against each opponent, in margin (each swap past a rank above gains 2, past an equal 1).
+    fn needs(&self, sum_matrix: &SumMatrix, cand: &str, opponents: &[String]) -> Vec<Rational>
+    {
+        margins(sum_matrix, cand, opponents).into_iter()
+            .map(|m| if m.is_positive() { Rational::zero() } else { Rational::one() - m })
+            .collect()
+    }
+
+    fn quick_score(&self, sum_matrix: &SumMatrix, cand: &str) -> Option<Rational>
+    {
+        if self.election.votes().is_empty()
+        {
+            return None;
+        }
+
+        let opponents: Vec<String> = self.election.candidates().into_iter().filter(|c| c != cand).collect();
+
+        // Swapping a voter past an opponent gains at most 2. With whole voters, only whole swaps count.
+        let whole = all_integer(&self.election);
+
+        Some(self.needs(sum_matrix, cand, &opponents).into_iter()
+            .map(|n| n / Rational::from(2))
+            .map(|half| if whole { Rational::new(half.ceil(), 1) } else { half })
+            .sum())
+    }
+
+    /// The exact score: Some(None) if it can't be done, None if the search is too large.
+    fn exact_score(&self, sum_matrix: &SumMatrix, cand: &str) -> Option<Option<Rational>>
+    {
+        let opponents: Vec<String> = self.election.candidates().into_iter().filter(|c| c != cand).collect();
+        let needs: Vec<u32> = self.needs(sum_matrix, cand, &opponents).into_iter().map(|n| n.numer() as u32).collect();
+
+        let states = needs.iter().fold(1usize, |total, &n| total.saturating_mul(n as usize + 1));
+        if states > self.max_states
+        {
+            return None;
+        }
+
+        // The fewest swaps that leave each shortfall.
+        let mut best: HashMap<Vec<u32>, u64> = HashMap::new();
+        best.insert(needs, 0);
+
+        for ballot in self.election.votes()
+        {
+            let options = swap_options(ballot, cand, &opponents);
+
+            // One voter per pass, moving on only from the shortfalls the last pass improved. An improvement
+            // never uses a voter who shrinks nothing, so the passes stop by the time the shortfalls could be
+            // used up, however many voters cast the ballot.
+            let mut improved = best.clone();
+
+            for _ in 0..ballot.weight.numer()
+            {
+                let mut next: HashMap<Vec<u32>, u64> = HashMap::new();
+
+                for (state, &cost) in &improved
+                {
+                    for &(swaps, ref gains) in options.iter().filter(|&&(swaps, _)| swaps > 0)
+                    {
+                        let after: Vec<u32> = state.iter().zip(gains).map(|(&n, &g)| n.saturating_sub(g)).collect();
+
+                        if best.get(&after).map_or(true, |&b| cost + swaps < b) && next.get(&after).map_or(true, |&b| cost + swaps < b)
+                        {
+                            next.insert(after, cost + swaps);
+                        }
+                    }
+                }
+
+                if next.is_empty()
+                {
+                    break;
+                }
+
+                best.extend(next.iter().map(|(state, &cost)| (state.clone(), cost)));
+                improved = next;
+            }
+        }
+
+        let done = vec![0; opponents.len()];
+
+        Some(best.get(&done).map(|&swaps| Rational::from(swaps)))
+    }
+}
+
+/**
+The ways one voter can move 'cand' up their ballot: the number of swaps, and how much each opponent's shortfall shrinks.
+'cand' passes those ranked equal first (a gain of 1 each), then each rank above in turn (2 each),
+passing any subset of the rank it stops in.
+*/
+fn swap_options(ballot: &Ballot, cand: &str, opponents: &[String]) -> Vec<(u64, Vec<u32>)>
+{
+    let pos = position(ballot, cand);
+    let mut groups: Vec<(Vec<usize>, u32)> = Vec::new();
+
+    let equal: Vec<usize> = (0..opponents.len()).filter(|&i| position(ballot, &opponents[i]) == pos).collect();
+    groups.push((equal, 1));
+
+    for rank in (0..pos).rev()
+    {
+        let above: Vec<usize> = (0..opponents.len()).filter(|&i| position(ballot, &opponents[i]) == rank).collect();
+        groups.push((above, 2));
+    }
+
+    let mut options = vec![(0, vec![0; opponents.len()])];
+    let mut passed = vec![0u32; opponents.len()];
+    let mut swaps = 0;
+
+    for (group, gain) in groups
+    {
+        if group.is_empty() { continue; }
+
+        for subset in 1..(1u64 << group.len())
+        {
+            let mut gains = passed.clone();
+            let mut cost = swaps;
+
+            for (_, &i) in group.iter().enumerate().filter(|&(k, _)| subset & (1 << k) != 0)
+            {
+                gains[i] = gain;
+                cost += 1;
+            }
+
+            options.push((cost, gains));
+        }
+
+        for &i in &group
+        {
+            passed[i] = gain;
+        }
+        swaps += group.len() as u64;
+    }
+
+    options
+}
+
+/**
+Young's method elects the candidate closest to being a Condorcet winner, measured in voters:
+a candidate's score is the fewest voters whose ballots would have to be removed for them to beat everyone else.
+
+The exact score is found by a search over how many voters to remove from each ballot. It needs whole-number
+ballot weights, and is limited by max_states(). Otherwise every score is approximated by removing voters greedily,
+whichever most reduces the candidate's total shortfall, which gives an upper bound.
+*/
+pub struct Young
+{
+    election: Election,
+    max_states: usize
+}
+
+impl Young
+{
+    pub fn with_election(election: &Election) -> Self
+    {
+        Young{ election: election.clone(), max_states: DEFAULT_MAX_STATES }
+    }
+
+    /// Limits the exact search (DEFAULT_MAX_STATES by default). Zero always approximates.
+    pub fn max_states(mut self, max_states: usize) -> Self
+    {
+        self.max_states = max_states;
+        self
+    }
+
+    /**
+    Gets each candidate's score (None if they can't become the Condorcet winner), and whether the scores are exact.
+    */
+    pub fn get_result(&self) -> DistanceResult
+    {
+        let candidates = self.election.candidates();
+        let sum_matrix = SumMatrix::new(&self.election);
+
+        let exact = if all_integer(&self.election)
+        {
+            candidates.iter().map(|c| self.exact_score(&sum_matrix, c)).collect::<Option<Vec<_>>>()
+        }
+        else {
+            None
+        };
+
+        match exact
+        {
+            Some(scores) => DistanceResult::new(candidates.into_iter().zip(scores).collect(), true),
+            None => {
+                let scores = candidates.iter().map(|c| self.greedy_score(&sum_matrix, c)).collect::<Vec<_>>();
+                DistanceResult::new(candidates.into_iter().zip(scores).collect(), false)
+            }
+        }
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+
+    /// How removing one voter of each ballot changes each margin of 'cand': +1 if they prefer the opponent, -1 if 'cand'.
+    fn effects(&self, cand: &str, opponents: &[String]) -> Vec<Vec<i128>>
+    {
+        self.election.votes().iter().map(|ballot| {
+            let pos = position(ballot, cand);
+
+            opponents.iter().map(|d| {
+                let other = position(ballot, d);
+                if other < pos { 1 } else if other > pos { -1 } else { 0 }
+            }).collect()
+        }).collect()
+    }
+
+    fn exact_score(&self, sum_matrix: &SumMatrix, cand: &str) -> Option<Option<Rational>>
+    {
+        let opponents: Vec<String> = self.election.candidates().into_iter().filter(|c| c != cand).collect();
+        let ballots = self.election.votes();
+
+        let states = ballots.iter().fold(1usize, |total, b| total.saturating_mul(b.weight.numer() as usize + 1));
+        if states > self.max_states
+        {
+            return None;
+        }
+
+        let effects = self.effects(cand, &opponents);
+        let start: Vec<i128> = margins(sum_matrix, cand, &opponents).into_iter().map(|m| m.numer()).collect();
+
+        // The fewest voters removed that leave each set of margins.
+        let mut best: HashMap<Vec<i128>, i128> = HashMap::new();
+        best.insert(start, 0);
+
+        for (ballot, effect) in ballots.iter().zip(&effects)
+        {
+            let mut next: HashMap<Vec<i128>, i128> = HashMap::new();
+
+            for (state, &removed) in &best
+            {
+                for count in 0..(ballot.weight.numer() + 1)
+                {
+                    let after: Vec<i128> = state.iter().zip(effect).map(|(&m, &e)| m + e * count).collect();
+                    let entry = next.entry(after).or_insert(i128::max_value());
+
+                    if removed + count < *entry
+                    {
+                        *entry = removed + count;
+                    }
+                }
+            }
+
+            best = next;
+        }
+
+        let score = best.iter().filter(|&(state, _)| state.iter().all(|&m| m > 0)).map(|(_, &removed)| removed).min();
+
+        Some(score.map(|removed| Rational::new(removed, 1)))
+    }
+
+    fn greedy_score(&self, sum_matrix: &SumMatrix, cand: &str) -> Option<Rational>
+    {
+        let opponents: Vec<String> = self.election.candidates().into_iter().filter(|c| c != cand).collect();
+        let effects = self.effects(cand, &opponents);
+        let mut left: Vec<Rational> = self.election.votes().iter().map(|b| b.weight).collect();
+        let mut state = margins(sum_matrix, cand, &opponents);
+        let mut removed = Rational::zero();
+
+        let shortfall = |state: &[Rational]| -> Rational {
+            state.iter().filter(|m| !m.is_positive()).map(|&m| Rational::one() - m).sum()
+        };
+
+        while !shortfall(&state).is_zero()
+        {
+            // Remove up to one voter from whichever ballot helps most.
+            let step = (0..left.len())
+                .filter(|&b| left[b].is_positive())
+                .map(|b| {
+                    let amount = left[b].min(Rational::one());
+                    let after: Vec<Rational> = state.iter().zip(&effects[b]).map(|(&m, &e)| m + Rational::from(e as i64) * amount).collect();
+                    (shortfall(&state) - shortfall(&after), Reverse(b), amount, after)
+                })
+                .filter(|&(gain, _, _, _)| gain.is_positive())
+                .max_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+
+            match step
+            {
+                Some((_, Reverse(b), amount, after)) => {
+                    left[b] -= amount;
+                    removed += amount;
+                    state = after;
+                },
+                None => return None
+            }
+        }
+
+        Some(removed)
+    }
+}
+
+/// The outcome of Dodgson or Young: lower scores are better.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceResult
+{
+    /// Each candidate's score, by name. None if nothing can make them the Condorcet winner.
+    pub scores: Vec<(String, Option<Rational>)>,
+    /// The candidates from lowest score to highest, with those without a score last.
+    pub ranking: Vec<Vec<String>>,
+    pub winners: Vec<String>,
+    /// Whether the scores are exact, or all approximated.
+    pub exact: bool
+}
+
+impl DistanceResult
+{
+    fn new(scores: Vec<(String, Option<Rational>)>, exact: bool) -> Self
+    {
+        let ranking = rank_by(scores.iter().map(|&(ref c, s)| (c.clone(), Reverse((s.is_none(), s.unwrap_or_default())))).collect());
+        let winners = ranking.first().cloned().unwrap_or_default();
+
+        DistanceResult { scores: scores, ranking: ranking, winners: winners, exact: exact }
+    }
+
+    pub fn score(&self, cand: &str) -> Option<Rational>
+    {
+        self.scores.iter().find(|&&(ref c, _)| c == cand).and_then(|&(_, s)| s)
+    }
+}
+
+
+#[cfg(test)]
+mod dodgson_tests
+{
+    use super::*;
+    use fixtures;
+
+    #[test]
+    fn dodgson()
+    {
+        let mut election = Election::new();
+
+        // A beats C 3-2 and ties B 2-2 with one voter indifferent; B beats C 4-1.
+        election.add_ballots("
+        2:A>B>C
+        1:B>C>A
+        1:C>B>A
+        1:A=B>C").unwrap();
+
+        let result = Dodgson::with_election(&election).get_result();
+
+        assert!(result.exact);
+        // Either A or B wins by breaking the tie on the A=B>C ballot, with one swap.
+        assert_eq!(result.score("A"), Some(1.into()));
+        assert_eq!(result.score("B"), Some(1.into()));
+        assert_eq!(result.winners, vec!["A", "B"]);
+        // C passes both A and B on the A=B>C ballot, and B on a B>C>A ballot.
+        assert_eq!(result.score("C"), Some(3.into()));
+
+        let quick = Dodgson::with_election(&election).max_states(0).get_result();
+        assert!(!quick.exact);
+        assert!(quick.score("C") <= result.score("C"));
+    }
+
+    #[test]
+    fn many_voters()
+    {
+        let mut election = Election::new();
+
+        // B has to pass A on just over half the ballots. Each pass only looks at the one shortfall the last improved.
+        election.add_ballots("100000:A>B").unwrap();
+
+        let result = Dodgson::with_election(&election).get_result();
+
+        assert!(result.exact);
+        assert_eq!(result.score("B"), Some(50001.into()));
+    }
+
+    #[test]
+    fn condorcet_winner()
+    {
+        let election = fixtures::tennessee();
+
+        let dodgson = Dodgson::with_election(&election).get_result();
+        assert!(dodgson.exact);
+        assert_eq!(dodgson.score("Nashville"), Some(0.into()));
+        // Memphis loses every matchup 42-58, so needs 9 voters to move up past each opponent.
+        // Knoxville and Nashville are each just above Memphis on some ballot, but Chattanooga never is.
+        assert_eq!(dodgson.score("Memphis"), Some(Rational::from(9 * 2 + 9)));
+        assert_eq!(dodgson.winners, vec!["Nashville"]);
+
+        let young = Young::with_election(&election).get_result();
+        assert!(young.exact);
+        assert_eq!(young.score("Nashville"), Some(0.into()));
+        // Chattanooga only loses to Nashville, 32-68, so 37 of the voters who prefer Nashville have to go.
+        assert_eq!(young.score("Chattanooga"), Some(37.into()));
+        assert_eq!(young.winners, vec!["Nashville"]);
+    }
+
+    #[test]
+    fn young()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        3:A>B>C
+        2:B>C>A
+        2:C>A>B").unwrap();
+
+        let exact = Young::with_election(&election).get_result();
+
+        // A only loses to C, 3-4, and removing two voters who prefer C fixes that.
+        assert!(exact.exact);
+        assert_eq!(exact.score("A"), Some(2.into()));
+        assert_eq!(exact.score("B"), Some(4.into()));
+        assert_eq!(exact.score("C"), Some(4.into()));
+        assert_eq!(exact.winners, vec!["A"]);
+
+        let greedy = Young::with_election(&election).max_states(0).get_result();
+        assert!(!greedy.exact);
+        assert!(greedy.score("A") >= exact.score("A"));
+    }
+}
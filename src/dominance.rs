@@ -0,0 +1,198 @@
+use sum_matrix::SumMatrix;
+use graph::Graph;
+use dfs::neighboring::Neighboring;
+
+
+/// Which set of undominated candidates to restrict a count to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DominantSet
+{
+    Smith,
+    Schwartz
+}
+
+impl DominantSet
+{
+    pub fn of(&self, sum_matrix: &SumMatrix) -> Vec<String>
+    {
+        match *self
+        {
+            DominantSet::Smith => sum_matrix.smith_set(),
+            DominantSet::Schwartz => sum_matrix.schwartz_set()
+        }
+    }
+}
+
+impl SumMatrix
+{
+    /**
+    Splits the candidates into dominance tiers, best first.
+    Everyone in a tier beats everyone in every later tier, and no smaller group does so.
+    Candidates who tie a pairwise matchup always end up in the same tier,
+    since neither beats the other.
+    The first tier is the Smith set.
+    */
+    pub fn smith_tiers(&self) -> Vec<Vec<String>>
+    {
+        let candidates = self.candidates();
+        let counts = self.counts(&candidates);
+        let n = candidates.len();
+
+        // An edge from each candidate to everyone they don't lose to.
+        let mut graph = Graph::new();
+
+        for i in 0..n
+        {
+            graph.add_node(candidates[i].clone());
+
+            for j in (0..n).filter(|&j| j != i && counts[i][j] >= counts[j][i])
+            {
+                graph.add_edge(candidates[i].clone(), candidates[j].clone());
+            }
+        }
+
+        // Any two candidates have an edge between them, so the components form a single chain,
+        // which strongly_connected_components() lists from the bottom up.
+        let mut tiers = graph.strongly_connected_components();
+        tiers.reverse();
+
+        for tier in &mut tiers
+        {
+            tier.sort();
+        }
+
+        tiers
+    }
+
+    /**
+    The Smith set: the smallest non-empty set of candidates who each beat everyone outside it.
+    */
+    pub fn smith_set(&self) -> Vec<String>
+    {
+        self.smith_tiers().into_iter().next().unwrap_or_default()
+    }
+
+    /**
+    Splits the Schwartz set into its minimal undominated sets:
+    groups of candidates who can each reach the others through chains of pairwise victories,
+    and who aren't beaten by anyone outside the group.
+    Unlike the Smith set, a pairwise tie doesn't join two candidates, so there may be several groups.
+    */
+    pub fn schwartz_components(&self) -> Vec<Vec<String>>
+    {
+        let candidates = self.candidates();
+        let counts = self.counts(&candidates);
+        let n = candidates.len();
+
+        // An edge from each candidate to everyone who beats them.
+        let mut graph = Graph::new();
+
+        for i in 0..n
+        {
+            graph.add_node(candidates[i].clone());
+
+            for j in (0..n).filter(|&j| counts[j][i] > counts[i][j])
+            {
+                graph.add_edge(candidates[i].clone(), candidates[j].clone());
+            }
+        }
+
+        let mut components: Vec<Vec<String>> = graph.strongly_connected_components()
+            .into_iter()
+            .filter(|component| component.iter().all(|member| graph.get_neighbors(member).all(|n| component.contains(n))))
+            .collect();
+
+        for component in &mut components
+        {
+            component.sort();
+        }
+        components.sort();
+
+        components
+    }
+
+    /**
+    The Schwartz set: every candidate in a minimal undominated set.
+    It's always within the Smith set, and smaller than it only when there are pairwise ties.
+    */
+    pub fn schwartz_set(&self) -> Vec<String>
+    {
+        let mut set: Vec<String> = self.schwartz_components().into_iter().flat_map(|c| c.into_iter()).collect();
+        set.sort();
+        set
+    }
+
+    /**
+    The candidate who beats every other candidate, if there is one.
+    */
+    pub fn condorcet_winner(&self) -> Option<String>
+    {
+        let smith_set = self.smith_set();
+
+        if smith_set.len() == 1
+        {
+            return smith_set.into_iter().next();
+        }
+
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod dominance_tests
+{
+    use election::Election;
+    use sum_matrix::SumMatrix;
+    use fixtures;
+
+    #[test]
+    fn tiers()
+    {
+        let mut election = Election::new();
+
+        // A, B and C beat each other in a cycle, and all beat D and E. D ties E, and both beat F.
+        election.add_ballots("
+        1:A>B>C>D=E>F
+        1:B>C>A>D=E>F
+        1:C>A>B>D=E>F
+        1:D>E
+        1:E>D").unwrap();
+
+        let sum_matrix = SumMatrix::new(&election);
+
+        assert_eq!(sum_matrix.smith_tiers(), vec![vec!["A", "B", "C"], vec!["D", "E"], vec!["F"]]);
+        assert_eq!(sum_matrix.smith_set(), vec!["A", "B", "C"]);
+        assert_eq!(sum_matrix.schwartz_components(), vec![vec!["A", "B", "C"]]);
+        assert_eq!(sum_matrix.condorcet_winner(), None);
+    }
+
+    #[test]
+    fn schwartz_with_ties()
+    {
+        let mut election = Election::new();
+
+        // A ties B and C, B beats C, and everyone beats D.
+        election.add_ballots("
+        1:A>B>C>D
+        1:B>C>A>D
+        1:C>D").unwrap();
+
+        let sum_matrix = SumMatrix::new(&election);
+
+        assert_eq!(sum_matrix.smith_set(), vec!["A", "B", "C"]);
+        assert_eq!(sum_matrix.schwartz_components(), vec![vec!["A"], vec!["B"]]);
+        assert_eq!(sum_matrix.schwartz_set(), vec!["A", "B"]);
+    }
+
+    #[test]
+    fn condorcet_winner()
+    {
+        let election = fixtures::tennessee();
+
+        let sum_matrix = SumMatrix::new(&election);
+
+        assert_eq!(sum_matrix.condorcet_winner(), Some("Nashville".to_owned()));
+        assert_eq!(sum_matrix.smith_tiers().len(), 4);
+    }
+}
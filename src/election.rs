@@ -1,7 +1,26 @@
+use rational::{Rational, ParseRationalError};
 
+/// A single ranked ballot and how much it counts for.
+/// ranks[0] holds the most preferred candidates; candidates in the same rank are equal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ballot
+{
+    pub weight: Rational,
+    pub ranks: Vec<Vec<String>>
+}
+
+impl Ballot
+{
+    pub fn new(weight: Rational, ranks: Vec<Vec<String>>) -> Self
+    {
+        Ballot { weight: weight, ranks: ranks }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Election
 {
-    pub ballots: Vec<Vec<Vec<String>>>
+    pub ballots: Vec<Ballot>
 }
 
 impl Election
@@ -11,82 +30,363 @@ impl Election
         Election {ballots: Vec::new()}
     }
 
-    pub fn votes(&self) -> &[Vec<Vec<String>>]
+    pub fn votes(&self) -> &[Ballot]
     {
         return &self.ballots[..];
     }
+
+    /// Every candidate ranked on any ballot, in sorted order.
+    pub fn candidates(&self) -> Vec<String>
+    {
+        let mut candidates: Vec<String> = self.ballots.iter()
+            .flat_map(|ballot| ballot.ranks.iter())
+            .flat_map(|rank| rank.iter())
+            .cloned()
+            .collect();
+
+        candidates.sort();
+        candidates.dedup();
+        candidates
+    }
+
+    /**
+    The same ballots with only 'candidates' left on them. Everyone else is struck off, along with any ranks
+    left empty; ballots that rank none of 'candidates' are kept, without any ranks, so the total weight is unchanged.
+    */
+    pub fn restricted(&self, candidates: &[String]) -> Election
+    {
+        let ballots = self.ballots.iter()
+            .map(|ballot| {
+                let ranks = ballot.ranks.iter()
+                    .map(|rank| rank.iter().filter(|&c| candidates.contains(c)).cloned().collect::<Vec<String>>())
+                    .filter(|rank| !rank.is_empty())
+                    .collect();
+
+                Ballot::new(ballot.weight, ranks)
+            })
+            .collect();
+
+        Election { ballots: ballots }
+    }
     
 
-    /// Parses a String vote.
+    /// Parses a String vote with a weight of 1.
     /// "A>B=C>D" creates [[a], [b, c], [d]]
-    pub fn add_vote(&mut self, data: &str)
+    /// The vote is checked with the default ValidationPolicy, which rejects any problem.
+    pub fn add_vote(&mut self, data: &str) -> Result<(), ElectionParseError>
     {
-        let mut ballot: Vec<Vec<String>> = Vec::new();
+        self.add_vote_with(Rational::one(), data, &ValidationPolicy::default()).map(|_| ())
+    }
 
-        for rank in data.split('>')
-        {
-            let mut ranks: Vec<String> = Vec::new();
+    /// Parses a String vote that counts for 'weight' ballots, checking it against 'policy'.
+    /// Returns what happened to the ballot (counted as-is, repaired or spoiled).
+    pub fn add_vote_with(&mut self, weight: Rational, data: &str, policy: &ValidationPolicy) -> Result<BallotStatus, ElectionParseError>
+    {
+        let (ballot, status) = parse_vote(weight, data, policy)?;
 
-            for c in rank.split('=')
-            {
-                ranks.push(c.to_owned());
-            }
-            ballot.push(ranks);
-        }
+        self.ballots.extend(ballot);
 
-        self.ballots.push(ballot);
+        return Ok(status);
     }
 
     /// Parses a String into ballots.
     /// Format: Per line:
     /// [amount]:[vote]
+    /// [amount] is a weight: "42", "0.5" or "2/3".
     /// (See self.add_vote() for [vote])
-    pub fn add_ballots(&mut self, data: &str) -> Result<(), ElectionParseError>
+    /// Ballots are checked with the default ValidationPolicy.
+    pub fn add_ballots(&mut self, data: &str) -> Result<BallotSummary, ElectionParseError>
     {
+        self.add_ballots_with(data, &ValidationPolicy::default())
+    }
+
+    /// Parses a String into ballots, checking each one against 'policy'.
+    /// Returns how many ballots were counted, repaired or spoiled, and how many hit each problem.
+    /// If any line can't be parsed or is rejected, none of the ballots are added.
+    pub fn add_ballots_with(&mut self, data: &str, policy: &ValidationPolicy) -> Result<BallotSummary, ElectionParseError>
+    {
+        let mut summary = BallotSummary::default();
+        let mut ballots = Vec::new();
+
         for line in data.trim().lines()
         {
-            let mut parts = line.trim().split(':');
+            let (amount, vote) = parse_line(line)?;
 
-            let amount_str = match parts.next()
-            {
-                Some(amount_str) => {
-                    if amount_str.len() == 0 {
-                        return Err(ElectionParseError::NoAmountError)
-                    } else {
-                        amount_str
-                    }
-                },
-                None => return Err(ElectionParseError::NoAmountError)
-            };
-            
-            let vote = match parts.next()
-            {
-                Some(vote) => vote,
-                None => return Err(ElectionParseError::NoVoteError)
-            };
+            let (ballot, status) = parse_vote(amount, vote, policy)?;
+            ballots.extend(ballot);
+            summary.record(amount, &status);
+        }
 
-            if let Some(_) = parts.next()
-            {
-                return Err(ElectionParseError::ExcessDataError);
+        self.ballots.extend(ballots);
+
+        return Ok(summary);
+    }
+}
+
+/// Parses and checks one vote. The ballot is None if it's spoiled.
+fn parse_vote(weight: Rational, data: &str, policy: &ValidationPolicy) -> Result<(Option<Ballot>, BallotStatus), ElectionParseError>
+{
+    let mut ballot: Vec<Vec<String>> = Vec::new();
+
+    for rank in data.split('>')
+    {
+        let mut ranks: Vec<String> = Vec::new();
+
+        for c in rank.split('=')
+        {
+            ranks.push(c.to_owned());
+        }
+        ballot.push(ranks);
+    }
+
+    let status = policy.check(&mut ballot)?;
+
+    if status.is_spoiled()
+    {
+        return Ok((None, status));
+    }
+
+    Ok((Some(Ballot::new(weight, ballot)), status))
+}
+
+/// Splits one "[amount]:[vote]" line into its weight and its vote.
+/// Shared by every ballot format that uses amounts.
+pub fn parse_line(line: &str) -> Result<(Rational, &str), ElectionParseError>
+{
+    let mut parts = line.trim().split(':');
+
+    let amount_str = match parts.next()
+    {
+        Some(amount_str) => {
+            if amount_str.is_empty() {
+                return Err(ElectionParseError::NoAmountError)
+            } else {
+                amount_str
             }
-            
-            let amount = match amount_str.parse::<u32>()
-            {
-                Ok(amount) => amount,
-                Err(e) => return Err(ElectionParseError::AmountParseError(e))
-            };
+        },
+        None => return Err(ElectionParseError::NoAmountError)
+    };
+    
+    let vote = match parts.next()
+    {
+        Some(vote) => vote,
+        None => return Err(ElectionParseError::NoVoteError)
+    };
 
-            for _ in 0..amount
+    if parts.next().is_some()
+    {
+        return Err(ElectionParseError::ExcessDataError);
+    }
+    
+    let amount = match amount_str.parse::<Rational>()
+    {
+        Ok(amount) => amount,
+        Err(e) => return Err(ElectionParseError::AmountParseError(e))
+    };
+
+    if amount.is_negative()
+    {
+        return Err(ElectionParseError::NegativeAmountError);
+    }
+
+    return Ok((amount, vote));
+}
+
+
+/// Something wrong with a ballot that would otherwise be tallied as-is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BallotProblem
+{
+    /// A candidate is ranked more than once, as in "A>B>A".
+    DuplicateCandidate,
+    /// A rank (or a name within a rank) is empty, as in "A>>B" or "A=>B".
+    EmptyRank
+}
+
+/// What to do with a ballot that has a given problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BallotPolicy
+{
+    /// Stop parsing with an InvalidBallotError.
+    Reject,
+    /// Keep only the first occurrence of each candidate and drop empty ranks.
+    /// A ballot left with no candidates, such as a blank one, is spoiled instead.
+    Repair,
+    /// Leave the ballot out of the election, but count it as spoiled.
+    Spoil
+}
+
+/// Chooses a BallotPolicy for each BallotProblem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationPolicy
+{
+    pub duplicate_candidate: BallotPolicy,
+    pub empty_rank: BallotPolicy
+}
+
+impl Default for ValidationPolicy
+{
+    fn default() -> Self
+    {
+        ValidationPolicy::all(BallotPolicy::Reject)
+    }
+}
+
+impl ValidationPolicy
+{
+    /// Uses the same policy for every problem.
+    pub fn all(policy: BallotPolicy) -> Self
+    {
+        ValidationPolicy { duplicate_candidate: policy, empty_rank: policy }
+    }
+
+    pub fn policy_for(&self, problem: BallotProblem) -> BallotPolicy
+    {
+        match problem
+        {
+            BallotProblem::DuplicateCandidate => self.duplicate_candidate,
+            BallotProblem::EmptyRank => self.empty_rank
+        }
+    }
+
+    /// Finds the ballot's problems and applies the policy for each.
+    /// A rejection wins over spoiling, and spoiling wins over repairing.
+    /// 'ballot' is only modified if it's repaired (or repairing empties it, and it's spoiled).
+    fn check(&self, ballot: &mut Vec<Vec<String>>) -> Result<BallotStatus, ElectionParseError>
+    {
+        let problems = find_problems(ballot);
+
+        if problems.is_empty()
+        {
+            return Ok(BallotStatus::Valid);
+        }
+
+        for &problem in &problems
+        {
+            if self.policy_for(problem) == BallotPolicy::Reject
             {
-                self.add_vote(vote);
+                return Err(ElectionParseError::InvalidBallotError(problem));
             }
         }
 
-        return Ok(());
+        if problems.iter().any(|&p| self.policy_for(p) == BallotPolicy::Spoil)
+        {
+            return Ok(BallotStatus::Spoiled(problems));
+        }
+
+        repair(ballot);
+
+        // Nothing is left to count, e.g. on a blank ballot.
+        if ballot.is_empty()
+        {
+            return Ok(BallotStatus::Spoiled(problems));
+        }
+
+        Ok(BallotStatus::Repaired(problems))
     }
 }
 
-use std::num::ParseIntError;
+/// Lists each problem a ballot has, once.
+fn find_problems(ballot: &[Vec<String>]) -> Vec<BallotProblem>
+{
+    use std::collections::HashSet;
+
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut problems = Vec::new();
+
+    let empty = ballot.iter().flat_map(|rank| rank.iter()).any(|c| c.is_empty());
+    let duplicate = !ballot.iter().flat_map(|rank| rank.iter()).filter(|c| !c.is_empty()).all(|c| seen.insert(c));
+
+    if duplicate { problems.push(BallotProblem::DuplicateCandidate); }
+    if empty { problems.push(BallotProblem::EmptyRank); }
+
+    problems
+}
+
+/// Drops empty names, later occurrences of a candidate, and any rank left empty.
+fn repair(ballot: &mut Vec<Vec<String>>)
+{
+    use std::collections::HashSet;
+
+    let mut seen: HashSet<String> = HashSet::new();
+
+    for rank in ballot.iter_mut()
+    {
+        rank.retain(|c| !c.is_empty() && seen.insert(c.clone()));
+    }
+
+    ballot.retain(|rank| !rank.is_empty());
+}
+
+/// What happened to a single ballot when it was added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BallotStatus
+{
+    Valid,
+    Repaired(Vec<BallotProblem>),
+    Spoiled(Vec<BallotProblem>)
+}
+
+impl BallotStatus
+{
+    pub fn is_spoiled(&self) -> bool
+    {
+        match *self
+        {
+            BallotStatus::Spoiled(_) => true,
+            _ => false
+        }
+    }
+
+    pub fn problems(&self) -> &[BallotProblem]
+    {
+        match *self
+        {
+            BallotStatus::Valid => &[],
+            BallotStatus::Repaired(ref problems) | BallotStatus::Spoiled(ref problems) => problems
+        }
+    }
+}
+
+/// Counts (total weights) of what happened to the ballots of one add_ballots() call.
+/// A ballot with several problems is counted under each of them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BallotSummary
+{
+    pub valid: Rational,
+    pub repaired: Rational,
+    pub spoiled: Rational,
+    pub duplicate_candidate: Rational,
+    pub empty_rank: Rational
+}
+
+impl BallotSummary
+{
+    fn record(&mut self, weight: Rational, status: &BallotStatus)
+    {
+        match *status
+        {
+            BallotStatus::Valid => self.valid += weight,
+            BallotStatus::Repaired(_) => self.repaired += weight,
+            BallotStatus::Spoiled(_) => self.spoiled += weight
+        }
+
+        for problem in status.problems()
+        {
+            match *problem
+            {
+                BallotProblem::DuplicateCandidate => self.duplicate_candidate += weight,
+                BallotProblem::EmptyRank => self.empty_rank += weight
+            }
+        }
+    }
+
+    /// Ballots that ended up in the election.
+    pub fn counted(&self) -> Rational
+    {
+        self.valid + self.repaired
+    }
+}
 
 #[derive(Debug)]
 pub enum ElectionParseError
@@ -94,7 +394,12 @@ pub enum ElectionParseError
     NoAmountError,
     NoVoteError,
     ExcessDataError,
-    AmountParseError(ParseIntError)
+    AmountParseError(ParseRationalError),
+    NegativeAmountError,
+    InvalidBallotError(BallotProblem),
+    NoScoreError,
+    ScoreParseError(ParseRationalError),
+    ScoreRangeError
 }
 
 use std::fmt;
@@ -102,7 +407,11 @@ impl fmt::Display for ElectionParseError
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
     {
-        write!(f, "{}", self.description())
+        match *self
+        {
+            ElectionParseError::AmountParseError(ref e) | ElectionParseError::ScoreParseError(ref e) => write!(f, "{}", e),
+            _ => write!(f, "{}", self.description())
+        }
     }
 }
 
@@ -117,7 +426,114 @@ impl Error for ElectionParseError
             ElectionParseError::NoAmountError => "could not find amount",
             ElectionParseError::NoVoteError => "could not find vote",
             ElectionParseError::ExcessDataError => "too many sections",
-            ElectionParseError::AmountParseError(ref e) => e.description()
+            ElectionParseError::AmountParseError(_) => "could not parse amount",
+            ElectionParseError::NegativeAmountError => "amount is negative",
+            ElectionParseError::InvalidBallotError(BallotProblem::DuplicateCandidate) => "candidate ranked more than once",
+            ElectionParseError::InvalidBallotError(BallotProblem::EmptyRank) => "empty rank",
+            ElectionParseError::NoScoreError => "could not find score",
+            ElectionParseError::ScoreParseError(_) => "could not parse score",
+            ElectionParseError::ScoreRangeError => "score out of range"
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod election_tests
+{
+    use super::*;
+
+    #[test]
+    fn rejects_by_default()
+    {
+        let mut election = Election::new();
+
+        match election.add_ballots("3:A>B>A")
+        {
+            Err(ElectionParseError::InvalidBallotError(BallotProblem::DuplicateCandidate)) => (),
+            other => panic!("Expected a duplicate candidate error, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn repairs_ballots()
+    {
+        let mut election = Election::new();
+
+        let summary = election.add_ballots_with("
+        2:A>B>A
+        1:A>>B=C=
+        4:C>B>A", &ValidationPolicy::all(BallotPolicy::Repair)).unwrap();
+
+        let expected = BallotSummary { valid: 4.into(), repaired: 3.into(), spoiled: 0.into(), duplicate_candidate: 2.into(), empty_rank: 1.into() };
+
+        assert_eq!(summary, expected);
+        assert_eq!(election.votes()[0].ranks, vec![vec!["A".to_owned()], vec!["B".to_owned()]]);
+        assert_eq!(election.votes()[1].ranks, vec![vec!["A".to_owned()], vec!["B".to_owned(), "C".to_owned()]]);
+    }
+
+    #[test]
+    fn spoils_ballots()
+    {
+        let mut election = Election::new();
+        let policy = ValidationPolicy { duplicate_candidate: BallotPolicy::Spoil, empty_rank: BallotPolicy::Repair };
+
+        let summary = election.add_ballots_with("
+        2:A>B>A>
+        5:B>>A", &policy).unwrap();
+
+        let expected = BallotSummary { valid: 0.into(), repaired: 5.into(), spoiled: 2.into(), duplicate_candidate: 2.into(), empty_rank: 7.into() };
+
+        assert_eq!(summary, expected);
+        assert_eq!(election.votes().len(), 1);
+    }
+
+    #[test]
+    fn spoils_blank_repairs()
+    {
+        let mut election = Election::new();
+
+        // Repairing a blank ballot leaves nothing to count.
+        let summary = election.add_ballots_with("
+        1:
+        3:=>
+        2:A", &ValidationPolicy::all(BallotPolicy::Repair)).unwrap();
+
+        let expected = BallotSummary { valid: 2.into(), repaired: 0.into(), spoiled: 4.into(), duplicate_candidate: 0.into(), empty_rank: 4.into() };
+
+        assert_eq!(summary, expected);
+        assert_eq!(election.votes().len(), 1);
+    }
+
+    #[test]
+    fn all_or_nothing()
+    {
+        let mut election = Election::new();
+        election.add_ballots("1:A>B").unwrap();
+
+        // A rejected or unreadable line leaves the election as it was.
+        assert!(election.add_ballots("2:B>A\n3:A>B>A").is_err());
+        assert!(election.add_ballots("2:B>A\nB>A").is_err());
+        assert_eq!(election.votes().len(), 1);
+    }
+
+    #[test]
+    fn fractional_weights()
+    {
+        let mut election = Election::new();
+
+        let summary = election.add_ballots("
+        0.5:A>B
+        2/3:B>A
+        3:C").unwrap();
+
+        assert_eq!(summary.counted(), Rational::new(25, 6));
+        assert_eq!(election.votes()[1].weight, Rational::new(2, 3));
+
+        match election.add_ballots("-1:A>B")
+        {
+            Err(ElectionParseError::NegativeAmountError) => (),
+            other => panic!("Expected a negative amount error, got {:?}", other)
         }
     }
-}
\ No newline at end of file
+}
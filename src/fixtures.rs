@@ -0,0 +1,38 @@
+//! Elections shared by the tests.
+
+use election::Election;
+
+
+/**
+The Tennessee capital election: Memphis has the most first preferences but is everyone else's last choice,
+Nashville is the Condorcet winner, and IRV elects Knoxville.
+*/
+pub fn tennessee() -> Election
+{
+    let mut election = Election::new();
+
+    election.add_ballots("
+    42:Memphis>Nashville>Chattanooga>Knoxville
+    26:Nashville>Chattanooga>Knoxville>Memphis
+    15:Chattanooga>Knoxville>Nashville>Memphis
+    17:Knoxville>Chattanooga>Nashville>Memphis").unwrap();
+
+    election
+}
+
+/**
+An election that sets the Smith-set hybrids apart: B, C and D beat each other in a cycle
+(B>C 19-8, C>D 18-9, D>B 17-10) and all beat A 15-12.
+*/
+pub fn hybrids() -> Election
+{
+    let mut election = Election::new();
+
+    election.add_ballots("
+    9:A>D>B>C
+    8:C>D>B>A
+    3:A>B>C>D
+    7:B>C>D>A").unwrap();
+
+    election
+}
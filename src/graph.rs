@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Formatter, Display};
 
 use dfs::DFS;
@@ -25,6 +25,16 @@ T: Eq + Hash + Clone
         map.entry(from).or_insert(Vec::new()).push(to);
     }
 
+    /**
+    Adds a node with no edges, if it isn't already in the graph.
+    */
+    pub fn add_node(&mut self, node: T)
+    {
+        let Graph(ref mut map) = *self;
+
+        map.entry(node).or_insert(Vec::new());
+    }
+
     /**
     Finds the node that all nodes lead to.
     */
@@ -68,6 +78,71 @@ T: Eq + Hash + Clone
 
         return false;
     }
+
+    /**
+    Splits the graph into strongly connected components: groups of nodes that can all reach each other.
+    A node's component is everything it reaches that also reaches it back, found with a depth-first-search
+    each way. The components come out in reverse topological order:
+    no component has an edge to a component listed after it.
+    */
+    pub fn strongly_connected_components(&self) -> Vec<Vec<T>>
+    {
+        let Graph(ref map) = *self;
+
+        let mut nodes: Vec<&T> = map.keys().collect();
+        for neighbors in map.values()
+        {
+            for n in neighbors
+            {
+                if !map.contains_key(n) { nodes.push(n); }
+            }
+        }
+
+        let mut reversed = Graph::new();
+        for (from, neighbors) in map.iter()
+        {
+            for to in neighbors
+            {
+                reversed.add_edge(to.clone(), from.clone());
+            }
+        }
+
+        let mut assigned: HashSet<T> = HashSet::new();
+        let mut components: Vec<(usize, Vec<T>)> = Vec::new();
+
+        for node in nodes
+        {
+            if assigned.contains(node) { continue; }
+
+            let forward = self.reachable(node);
+            let backward = reversed.reachable(node);
+
+            let component: Vec<T> = forward.iter().filter(|n| backward.contains(n)).cloned().collect();
+            assigned.extend(component.iter().cloned());
+            components.push((forward.len(), component));
+        }
+
+        // A component with an edge to another reaches everything that one does, and more.
+        components.sort_by_key(|&(reach, _)| reach);
+
+        components.into_iter().map(|(_, component)| component).collect()
+    }
+
+    /**
+    Every node that can be reached from 'from', including itself.
+    */
+    fn reachable(&self, from: &T) -> HashSet<T>
+    {
+        let mut dfs = DFS::new(from);
+        let mut reached = HashSet::new();
+
+        while let Some(next) = dfs.next(self)
+        {
+            reached.insert(next);
+        }
+
+        reached
+    }
 }
 
 impl<T> Neighboring for Graph<T> where
@@ -0,0 +1,640 @@
+use std::collections::BTreeMap;
+
+use election::{Election, Ballot};
+use sum_matrix::SumMatrix;
+use dominance::DominantSet;
+use rational::Rational;
+use rounds::{Round, Transfer, EliminationResult};
+use rng::Rng;
+
+
+/// How to choose who to eliminate when several candidates tie for the lowest tally.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TieBreak
+{
+    /// Eliminate them all together. If that would eliminate everyone left, they tie for the win.
+    All,
+    /// Eliminate whoever had the lowest tally in the latest earlier round where the tied candidates differed.
+    /// If they were tied in every round, eliminate them all.
+    Backward,
+    /// Eliminate whoever comes last in this order. Candidates missing from it come after everyone listed, by name.
+    Order(Vec<String>),
+    /// Eliminate one of them at random. The same seed always makes the same choices.
+    Random(u64)
+}
+
+impl TieBreak
+{
+    /// Picks who to eliminate from 'tied', who have the lowest tally, given the earlier rounds.
+    pub fn losers(&self, mut tied: Vec<String>, rounds: &[Round], rng: &mut Rng) -> Vec<String>
+    {
+        tied.sort();
+
+        if tied.len() < 2
+        {
+            return tied;
+        }
+
+        match *self
+        {
+            TieBreak::All => tied,
+            TieBreak::Backward => {
+                for round in rounds.iter().rev()
+                {
+                    let lowest = tied.iter().map(|c| round.tally(c).unwrap_or_default()).min().unwrap_or_default();
+                    tied.retain(|c| round.tally(c).unwrap_or_default() == lowest);
+
+                    if tied.len() == 1 { break; }
+                }
+
+                tied
+            },
+            TieBreak::Order(ref order) => {
+                let last = tied.iter()
+                    .max_by_key(|&c| (order.iter().position(|o| o == c).unwrap_or(order.len()), c.clone()))
+                    .cloned();
+
+                last.into_iter().collect()
+            },
+            TieBreak::Random(_) => {
+                let i = rng.below(tied.len());
+                vec![tied.swap_remove(i)]
+            }
+        }
+    }
+
+    /**
+    Picks who to elect from 'tied', who have the highest tally, given the earlier rounds.
+    The mirror image of losers(): Backward keeps whoever had the highest tally in the latest round
+    where the tied candidates differed, and Order keeps whoever comes first.
+    */
+    pub fn winners(&self, mut tied: Vec<String>, rounds: &[Round], rng: &mut Rng) -> Vec<String>
+    {
+        tied.sort();
+
+        if tied.len() < 2
+        {
+            return tied;
+        }
+
+        match *self
+        {
+            TieBreak::All => tied,
+            TieBreak::Backward => {
+                for round in rounds.iter().rev()
+                {
+                    let highest = tied.iter().map(|c| round.tally(c).unwrap_or_default()).max().unwrap_or_default();
+                    tied.retain(|c| round.tally(c).unwrap_or_default() == highest);
+
+                    if tied.len() == 1 { break; }
+                }
+
+                tied
+            },
+            TieBreak::Order(ref order) => {
+                let first = tied.iter()
+                    .min_by_key(|&c| (order.iter().position(|o| o == c).unwrap_or(order.len()), c.clone()))
+                    .cloned();
+
+                first.into_iter().collect()
+            },
+            TieBreak::Random(_) => {
+                let i = rng.below(tied.len());
+                vec![tied.swap_remove(i)]
+            }
+        }
+    }
+
+    /// The generator for TieBreak::Random, seeded from it. Other tie-breaks never draw from it.
+    pub fn rng(&self) -> Rng
+    {
+        match *self
+        {
+            TieBreak::Random(seed) => Rng::new(seed),
+            _ => Rng::new(0)
+        }
+    }
+}
+
+/**
+How a ballot's weight is split among the 'continuing' candidates:
+evenly between those in the highest rank that has any of them. Empty if it ranks none of them.
+*/
+fn allocation(ballot: &Ballot, continuing: &[String]) -> Vec<(usize, Rational)>
+{
+    for rank in &ballot.ranks
+    {
+        let top: Vec<usize> = rank.iter().filter_map(|c| continuing.iter().position(|x| x == c)).collect();
+
+        if !top.is_empty()
+        {
+            let share = ballot.weight / Rational::from(top.len());
+            return top.into_iter().map(|i| (i, share)).collect();
+        }
+    }
+
+    Vec::new()
+}
+
+/**
+Counts each continuing candidate's first preferences, with ballots split evenly between equal first choices.
+Also returns the weight of the exhausted ballots, which rank none of them.
+*/
+pub fn first_preferences(election: &Election, continuing: &[String]) -> (Vec<(String, Rational)>, Rational)
+{
+    let mut tallies: Vec<(String, Rational)> = continuing.iter().map(|c| (c.clone(), Rational::zero())).collect();
+    let mut exhausted = Rational::zero();
+
+    for ballot in election.votes()
+    {
+        let shares = allocation(ballot, continuing);
+
+        if shares.is_empty()
+        {
+            exhausted += ballot.weight;
+        }
+
+        for (i, share) in shares
+        {
+            tallies[i].1 += share;
+        }
+    }
+
+    (tallies, exhausted)
+}
+
+/// Where the votes of candidates in 'before' but not in 'after' go, once they're eliminated.
+pub fn transfers(election: &Election, before: &[String], after: &[String]) -> Vec<Transfer>
+{
+    let mut amounts: BTreeMap<(String, Option<String>), Rational> = BTreeMap::new();
+
+    for ballot in election.votes()
+    {
+        let old = allocation(ballot, before);
+        let moved: Vec<(&String, Rational)> = old.iter()
+            .map(|&(i, share)| (&before[i], share))
+            .filter(|&(c, _)| !after.contains(c))
+            .collect();
+
+        let total: Rational = moved.iter().map(|&(_, share)| share).sum();
+        if total.is_zero() { continue; }
+
+        // Each continuing candidate's share can only grow, and it grows by what the eliminated ones lose.
+        let gains: Vec<(&String, Rational)> = allocation(ballot, after).into_iter()
+            .map(|(j, share)| {
+                let old_share = old.iter().find(|&&(i, _)| before[i] == after[j]).map_or(Rational::zero(), |&(_, s)| s);
+                (&after[j], share - old_share)
+            })
+            .filter(|&(_, gain)| gain.is_positive())
+            .collect();
+
+        for &(from, share) in &moved
+        {
+            if gains.is_empty()
+            {
+                *amounts.entry((from.clone(), None)).or_insert_with(Rational::zero) += share;
+            }
+
+            for &(to, gain) in &gains
+            {
+                *amounts.entry((from.clone(), Some(to.clone()))).or_insert_with(Rational::zero) += share * gain / total;
+            }
+        }
+    }
+
+    amounts.into_iter().map(|((from, to), amount)| Transfer { from: from, to: to, amount: amount }).collect()
+}
+
+/// When the counts built on first preferences stop, and who they consider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rule
+{
+    /// Stop as soon as a candidate has a majority of the ballots that aren't exhausted.
+    Irv,
+    /// Each round, drop everyone outside the set of those left. Stop once the set is a single candidate.
+    TidemanAlternative(DominantSet),
+    /// Stop as soon as a candidate beats everyone left pairwise.
+    Benham,
+    /// Stop once only one member of the Smith set of all the candidates is left.
+    Woodall,
+    /// Drop everyone outside the Smith set of all the candidates, then count as Irv.
+    SmithIrv
+}
+
+/**
+Runs an elimination count on first preferences, following 'rule'.
+Each round that doesn't find a winner eliminates the candidate with the fewest votes, and their ballots transfer.
+*/
+fn count(election: &Election, tie_break: &TieBreak, rule: Rule) -> EliminationResult
+{
+    let sum_matrix = SumMatrix::new(election);
+    let smith = if rule == Rule::Woodall || rule == Rule::SmithIrv { sum_matrix.smith_set() } else { Vec::new() };
+    let mut rng = tie_break.rng();
+    let mut remaining = election.candidates();
+    let mut rounds: Vec<Round> = Vec::new();
+
+    while !remaining.is_empty()
+    {
+        let contenders = match rule
+        {
+            Rule::TidemanAlternative(set) => set.of(&sum_matrix.restricted(&remaining)),
+            Rule::SmithIrv => remaining.iter().filter(|&c| smith.contains(c)).cloned().collect(),
+            _ => remaining.clone()
+        };
+        let excluded: Vec<String> = remaining.iter().filter(|&c| !contenders.contains(c)).cloned().collect();
+        let smith_left: Vec<String> = contenders.iter().filter(|&c| smith.contains(c)).cloned().collect();
+
+        let (tallies, exhausted) = first_preferences(election, &contenders);
+        let active: Rational = tallies.iter().map(|&(_, t)| t).sum();
+
+        let majority: Vec<String> = tallies.iter()
+            .filter(|&&(_, t)| t * Rational::from(2) > active)
+            .map(|&(ref c, _)| c.clone())
+            .collect();
+
+        let decided = match rule
+        {
+            Rule::Irv | Rule::SmithIrv if !majority.is_empty() => Some(majority),
+            Rule::Benham => sum_matrix.restricted(&contenders).condorcet_winner().map(|w| vec![w]),
+            Rule::Woodall if smith_left.len() == 1 => Some(smith_left.clone()),
+            _ => None
+        };
+
+        let winners = if contenders.len() == 1 || active.is_zero()
+        {
+            Some(contenders.clone())
+        }
+        else {
+            decided
+        };
+
+        let mut round = Round::new(tallies.clone(), excluded.clone());
+        round.exhausted = exhausted;
+
+        if let Some(winners) = winners
+        {
+            rounds.push(round);
+            return EliminationResult::new(winners, rounds);
+        }
+
+        let lowest = tallies.iter().map(|&(_, t)| t).min().unwrap_or_default();
+        let tied = tallies.iter().filter(|&&(_, t)| t == lowest).map(|&(ref c, _)| c.clone()).collect();
+        let losers = tie_break.losers(tied, &rounds, &mut rng);
+
+        // If the tied candidates are all that's left (of the Smith set, for Woodall), they tie for the win.
+        let last = if rule == Rule::Woodall { smith_left } else { contenders.clone() };
+
+        if last.iter().all(|c| losers.contains(c))
+        {
+            rounds.push(round);
+            return EliminationResult::new(last, rounds);
+        }
+
+        let next: Vec<String> = contenders.iter().filter(|&c| !losers.contains(c)).cloned().collect();
+
+        round.transfers = transfers(election, &contenders, &next);
+        round.eliminated.extend(losers);
+
+        rounds.push(round);
+        remaining = next;
+    }
+
+    EliminationResult::new(Vec::new(), rounds)
+}
+
+/**
+Instant-runoff voting (IRV, also called the alternative vote or ranked-choice voting) isn't a Condorcet method,
+but it's widely used, so it's here for comparison.
+Each ballot counts for its highest-ranked candidate still in the count, split evenly between equal candidates.
+If someone has a majority of the ballots that aren't exhausted, they win; otherwise the candidate with the fewest
+votes is eliminated and their ballots transfer to the next choices on them.
+*/
+pub struct Irv
+{
+    election: Election,
+    tie_break: TieBreak
+}
+
+impl Irv
+{
+    pub fn with_election(election: &Election) -> Self
+    {
+        Irv{ election: election.clone(), tie_break: TieBreak::Backward }
+    }
+
+    /// Sets how ties for elimination are broken (TieBreak::Backward by default).
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self
+    {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /**
+    Gets each round's tallies, exhausted ballots and transfers, and the winners.
+    */
+    pub fn get_result(&self) -> EliminationResult
+    {
+        count(&self.election, &self.tie_break, Rule::Irv)
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+/**
+Tideman's Alternative is a Condorcet-compliant variant of IRV. Each round, everyone outside the Smith set
+(or Schwartz set) of the candidates left is eliminated; if more than one candidate remains,
+the one with the fewest first preferences among them is eliminated too. This repeats until one is left.
+Ballots, equal rankings and ties are handled as in IRV.
+*/
+pub struct TidemanAlternative
+{
+    election: Election,
+    set: DominantSet,
+    tie_break: TieBreak
+}
+
+impl TidemanAlternative
+{
+    pub fn with_election(election: &Election, set: DominantSet) -> Self
+    {
+        TidemanAlternative{ election: election.clone(), set: set, tie_break: TieBreak::Backward }
+    }
+
+    /// Sets how ties for elimination are broken (TieBreak::Backward by default).
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self
+    {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /**
+    Gets each round's tallies and transfers, and the winners.
+    The candidates eliminated for being outside the set have no tally in that round.
+    */
+    pub fn get_result(&self) -> EliminationResult
+    {
+        count(&self.election, &self.tie_break, Rule::TidemanAlternative(self.set))
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+/**
+Benham's method is IRV with a Condorcet check: before each round, if one of the candidates left beats
+all the others pairwise, they win. Otherwise the candidate with the fewest first preferences is eliminated.
+*/
+pub struct Benham
+{
+    election: Election,
+    tie_break: TieBreak
+}
+
+impl Benham
+{
+    pub fn with_election(election: &Election) -> Self
+    {
+        Benham{ election: election.clone(), tie_break: TieBreak::Backward }
+    }
+
+    /// Sets how ties for elimination are broken (TieBreak::Backward by default).
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self
+    {
+        self.tie_break = tie_break;
+        self
+    }
+
+    pub fn get_result(&self) -> EliminationResult
+    {
+        count(&self.election, &self.tie_break, Rule::Benham)
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+/**
+Woodall's method orders the Smith set by IRV elimination: candidates are eliminated as in IRV
+(without stopping for a majority), and the last member of the Smith set of all the candidates left in wins.
+*/
+pub struct Woodall
+{
+    election: Election,
+    tie_break: TieBreak
+}
+
+impl Woodall
+{
+    pub fn with_election(election: &Election) -> Self
+    {
+        Woodall{ election: election.clone(), tie_break: TieBreak::Backward }
+    }
+
+    /// Sets how ties for elimination are broken (TieBreak::Backward by default).
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self
+    {
+        self.tie_break = tie_break;
+        self
+    }
+
+    pub fn get_result(&self) -> EliminationResult
+    {
+        count(&self.election, &self.tie_break, Rule::Woodall)
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+/**
+Smith//IRV eliminates everyone outside the Smith set, then counts the rest by IRV.
+Unlike Tideman's Alternative, the Smith set isn't recomputed after each elimination.
+*/
+pub struct SmithIrv
+{
+    election: Election,
+    tie_break: TieBreak
+}
+
+impl SmithIrv
+{
+    pub fn with_election(election: &Election) -> Self
+    {
+        SmithIrv{ election: election.clone(), tie_break: TieBreak::Backward }
+    }
+
+    /// Sets how ties for elimination are broken (TieBreak::Backward by default).
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self
+    {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /**
+    Gets each round's tallies and transfers, and the winners.
+    The candidates outside the Smith set are eliminated in the first round, without a tally.
+    */
+    pub fn get_result(&self) -> EliminationResult
+    {
+        count(&self.election, &self.tie_break, Rule::SmithIrv)
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+
+#[cfg(test)]
+mod irv_tests
+{
+    use super::*;
+    use fixtures;
+
+    #[test]
+    fn tennessee()
+    {
+        let election = fixtures::tennessee();
+
+        let result = Irv::with_election(&election).get_result();
+
+        assert_eq!(result.winners, vec!["Knoxville"]);
+        assert_eq!(result.rounds.len(), 3);
+        assert_eq!(result.rounds[0].transfers, vec![Transfer { from: "Chattanooga".to_owned(), to: Some("Knoxville".to_owned()), amount: 15.into() }]);
+        assert_eq!(result.rounds[1].transfers, vec![Transfer { from: "Nashville".to_owned(), to: Some("Knoxville".to_owned()), amount: 26.into() }]);
+        assert_eq!(result.rounds[2].tally("Knoxville"), Some(58.into()));
+        assert_eq!(result.ranking, vec![vec!["Knoxville"], vec!["Memphis"], vec!["Nashville"], vec!["Chattanooga"]]);
+
+        // Nashville is the Condorcet winner, so every hybrid elects them in the first round.
+        assert_eq!(TidemanAlternative::with_election(&election, DominantSet::Smith).get_winners(), vec!["Nashville"]);
+
+        for result in vec![Benham::with_election(&election).get_result(), Woodall::with_election(&election).get_result(),
+            SmithIrv::with_election(&election).get_result()]
+        {
+            assert_eq!(result.winners, vec!["Nashville"]);
+            assert_eq!(result.rounds.len(), 1);
+        }
+    }
+
+    #[test]
+    fn exhausted_and_equal()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        5:A
+        4:B
+        2:C>B
+        2:C=A
+        1:D").unwrap();
+
+        let result = Irv::with_election(&election).get_result();
+
+        assert_eq!(result.rounds[0].tally("A"), Some(6.into()));
+        assert_eq!(result.rounds[0].transfers, vec![Transfer { from: "D".to_owned(), to: None, amount: 1.into() }]);
+        assert_eq!(result.rounds[1].exhausted, 1.into());
+        assert_eq!(result.rounds[1].transfers, vec![
+            Transfer { from: "C".to_owned(), to: Some("A".to_owned()), amount: 1.into() },
+            Transfer { from: "C".to_owned(), to: Some("B".to_owned()), amount: 2.into() }
+        ]);
+
+        // 7 of the 13 ballots left is a majority.
+        assert_eq!(result.winners, vec!["A"]);
+        assert_eq!(result.rounds.len(), 3);
+    }
+
+    #[test]
+    fn tie_breaks()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        1:A>B>C
+        1:B>C>A
+        1:C>A>B").unwrap();
+
+        assert_eq!(Irv::with_election(&election).tie_break(TieBreak::All).get_winners(), vec!["A", "B", "C"]);
+        assert_eq!(Irv::with_election(&election).get_winners(), vec!["A", "B", "C"]);
+
+        let order = TieBreak::Order(vec!["A".to_owned(), "B".to_owned(), "C".to_owned()]);
+        assert_eq!(Irv::with_election(&election).tie_break(order).get_winners(), vec!["A"]);
+
+        let random = Irv::with_election(&election).tie_break(TieBreak::Random(9)).get_result();
+        assert_eq!(random.winners.len(), 1);
+        assert_eq!(random, Irv::with_election(&election).tie_break(TieBreak::Random(9)).get_result());
+    }
+
+    #[test]
+    fn backward()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        5:A
+        4:B
+        3:C
+        1:D>C").unwrap();
+
+        let result = Irv::with_election(&election).get_result();
+
+        // B and C tie on 4 in the second round, but C had fewer in the first.
+        assert_eq!(result.rounds[1].eliminated, vec!["C"]);
+        assert_eq!(result.winners, vec!["A"]);
+    }
+
+    #[test]
+    fn tideman_alternative()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        35:B>C>S>D
+        34:C>S>B>D
+        31:S>B>C>D").unwrap();
+
+        let result = TidemanAlternative::with_election(&election, DominantSet::Smith).get_result();
+
+        // D is outside the Smith set, and S has the fewest first preferences. Then B beats C.
+        assert_eq!(result.rounds[0].eliminated, vec!["D", "S"]);
+        assert_eq!(result.rounds[0].tally("D"), None);
+        assert_eq!(result.winners, vec!["B"]);
+        assert_eq!(result.ranking, vec![vec!["B"], vec!["C"], vec!["S"], vec!["D"]]);
+    }
+
+    #[test]
+    fn hybrids()
+    {
+        // B, C and D beat each other in a cycle (B>C 19-8, C>D 18-9, D>B 17-10) and all beat A 15-12.
+        let election = fixtures::hybrids();
+
+        // D has no first preferences and goes first, then B beats both A and C.
+        let benham = Benham::with_election(&election).get_result();
+        assert_eq!(benham.winners, vec!["B"]);
+        assert_eq!(benham.rounds[0].eliminated, vec!["D"]);
+        assert_eq!(benham.rounds.len(), 2);
+
+        // After D, B has the fewest votes, leaving C as the only Smith member.
+        let woodall = Woodall::with_election(&election).get_result();
+        assert_eq!(woodall.winners, vec!["C"]);
+        assert_eq!(woodall.rounds[1].eliminated, vec!["B"]);
+
+        // A is outside the Smith set, which gives D their first preferences; C goes next, and D has a majority.
+        let smith_irv = SmithIrv::with_election(&election).get_result();
+        assert_eq!(smith_irv.winners, vec!["D"]);
+        assert_eq!(smith_irv.rounds[0].eliminated, vec!["A", "C"]);
+        assert_eq!(smith_irv.rounds[0].tally("D"), Some(9.into()));
+        assert_eq!(smith_irv.rounds[1].tally("D"), Some(17.into()));
+
+        assert_eq!(Irv::with_election(&election).get_winners(), vec!["C"]);
+    }
+}
@@ -0,0 +1,341 @@
+use sum_matrix::SumMatrix;
+use election::Election;
+use rational::Rational;
+
+
+/// The most candidates Kemeny::get_result() will try; the work and memory double with each one.
+pub const MAX_EXACT_CANDIDATES: usize = 22;
+
+/**
+Kemeny–Young finds the ranking that agrees with the voters' pairwise preferences as much as possible:
+each ranking scores the number of voters who agree with each ordered pair in it, and the highest score wins.
+This is an exact solver, using dynamic programming over which candidates fill the top places,
+so it takes time and memory proportional to 2^n and is limited to MAX_EXACT_CANDIDATES.
+*/
+pub struct Kemeny
+{
+    sum_matrix: SumMatrix,
+    max_rankings: usize
+}
+
+impl Kemeny
+{
+    pub fn with_matrix(sum_matrix: SumMatrix) -> Self
+    {
+        Kemeny{ sum_matrix: sum_matrix, max_rankings: 1000 }
+    }
+
+    pub fn with_election(election: &Election) -> Self
+    {
+        Kemeny::with_matrix(SumMatrix::new(election))
+    }
+
+    /// Limits how many co-optimal rankings are listed (1000 by default).
+    pub fn max_rankings(mut self, max_rankings: usize) -> Self
+    {
+        self.max_rankings = max_rankings;
+        self
+    }
+
+    /**
+    Gets the optimal score and the optimal rankings.
+    */
+    pub fn get_result(&self) -> Result<KemenyResult, KemenyError>
+    {
+        let candidates = self.sum_matrix.candidates();
+
+        if candidates.len() > MAX_EXACT_CANDIDATES
+        {
+            return Err(KemenyError::TooManyCandidates(candidates.len()));
+        }
+
+        let (weights, scale) = integer_matrix(&self.sum_matrix.counts(&candidates));
+        let solution = best_orders(&weights, self.max_rankings);
+
+        Ok(KemenyResult
+        {
+            score: Rational::new(solution.score, scale),
+            rankings: solution.orders.into_iter()
+                .map(|order| order.into_iter().map(|i| candidates[i].clone()).collect())
+                .collect(),
+            complete: solution.complete
+        })
+    }
+}
+
+/// The outcome of an exact Kemeny count.
+pub struct KemenyResult
+{
+    /// The number of voter-pair agreements of the optimal rankings.
+    pub score: Rational,
+    /// Every optimal ranking, best candidate first (up to the limit set with max_rankings()).
+    pub rankings: Vec<Vec<String>>,
+    /// False if there were more optimal rankings than the limit.
+    pub complete: bool
+}
+
+impl KemenyResult
+{
+    /// Whether exactly one ranking is optimal.
+    pub fn is_unique(&self) -> bool
+    {
+        self.complete && self.rankings.len() == 1
+    }
+}
+
+/**
+Scales a matrix of fractions by their common denominator, so they can be added up quickly as integers.
+Returns the integers and the scale they were multiplied by.
+*/
+pub fn integer_matrix(matrix: &[Vec<Rational>]) -> (Vec<Vec<i128>>, i128)
+{
+    let mut scale = Rational::one();
+
+    // The least common multiple of the denominators.
+    for value in matrix.iter().flat_map(|row| row.iter())
+    {
+        scale = scale * Rational::new((scale / Rational::new(value.denom(), 1)).denom(), 1);
+    }
+
+    let weights = matrix.iter()
+        .map(|row| row.iter().map(|value| (*value * scale).numer()).collect())
+        .collect();
+
+    (weights, scale.numer())
+}
+
+/// Sums of weights[c][x] over any set of x, looked up in two halves so that each sum takes constant time.
+struct SubsetSums
+{
+    split: usize,
+    low: Vec<Vec<i128>>,
+    high: Vec<Vec<i128>>
+}
+
+impl SubsetSums
+{
+    fn new(weights: &[Vec<i128>]) -> Self
+    {
+        let n = weights.len();
+        let split = n / 2;
+
+        let table = |c: usize, offset: usize, bits: usize| -> Vec<i128>
+        {
+            let mut sums = vec![0i128; 1 << bits];
+
+            for mask in 1..(1usize << bits)
+            {
+                let low_bit = mask.trailing_zeros() as usize;
+                sums[mask] = sums[mask & (mask - 1)] + weights[c][offset + low_bit];
+            }
+
+            sums
+        };
+
+        SubsetSums
+        {
+            split: split,
+            low: (0..n).map(|c| table(c, 0, split)).collect(),
+            high: (0..n).map(|c| table(c, split, n - split)).collect()
+        }
+    }
+
+    /// The sum of weights[c][x] for every x in 'set'.
+    fn sum(&self, c: usize, set: usize) -> i128
+    {
+        self.low[c][set & ((1 << self.split) - 1)] + self.high[c][set >> self.split]
+    }
+}
+
+/// The best orders found by best_orders().
+pub struct Orders
+{
+    pub score: i128,
+    pub orders: Vec<Vec<usize>>,
+    pub complete: bool
+}
+
+/**
+Finds every order of 0..n that maximises the sum of weights[i][j] over each i placed above j,
+listing at most 'limit' of them.
+best[S] is the best score for placing the set S in the top |S| places. Adding c just below S gains
+weights[c][x] for every x not yet placed.
+*/
+pub fn best_orders(weights: &[Vec<i128>], limit: usize) -> Orders
+{
+    let n = weights.len();
+    let full = (1usize << n) - 1;
+    let sums = SubsetSums::new(weights);
+
+    let gain = |c: usize, placed: usize| sums.sum(c, full & !placed & !(1 << c));
+
+    let mut best = vec![i128::min_value(); 1 << n];
+    best[0] = 0;
+
+    for set in 0..full
+    {
+        if best[set] == i128::min_value() { continue; }
+
+        for c in (0..n).filter(|&c| set & (1 << c) == 0)
+        {
+            let score = best[set] + gain(c, set);
+            let next = set | (1 << c);
+
+            if score > best[next]
+            {
+                best[next] = score;
+            }
+        }
+    }
+
+    // Walk back from the full set, taking every last candidate that keeps the score optimal.
+    let mut search = OrderSearch
+    {
+        best: &best,
+        gain: gain,
+        n: n,
+        limit: limit,
+        suffix: Vec::new(),
+        orders: Vec::new(),
+        complete: true
+    };
+
+    search.collect_orders(full);
+
+    Orders { score: best[full], orders: search.orders, complete: search.complete }
+}
+
+/// The state of the walk back through best_orders()'s table.
+struct OrderSearch<'a, G>
+{
+    best: &'a [i128],
+    gain: G,
+    n: usize,
+    limit: usize,
+    /// The candidates placed below the current set, lowest first.
+    suffix: Vec<usize>,
+    orders: Vec<Vec<usize>>,
+    complete: bool
+}
+
+impl<'a, G> OrderSearch<'a, G> where G: Fn(usize, usize) -> i128
+{
+    fn collect_orders(&mut self, set: usize)
+    {
+        if set == 0
+        {
+            if self.orders.len() >= self.limit
+            {
+                self.complete = false;
+            }
+            else {
+                self.orders.push(self.suffix.iter().rev().cloned().collect());
+            }
+            return;
+        }
+
+        for c in (0..self.n).filter(|&c| set & (1 << c) != 0)
+        {
+            if !self.complete { return; }
+
+            let rest = set & !(1 << c);
+
+            if self.best[rest] != i128::min_value() && self.best[rest] + (self.gain)(c, rest) == self.best[set]
+            {
+                self.suffix.push(c);
+                self.collect_orders(rest);
+                self.suffix.pop();
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KemenyError
+{
+    /// More candidates than MAX_EXACT_CANDIDATES.
+    TooManyCandidates(usize)
+}
+
+use std::error::Error;
+use std::fmt::{self, Formatter, Display};
+
+impl Error for KemenyError
+{
+    fn description(&self) -> &str
+    {
+        match *self
+        {
+            KemenyError::TooManyCandidates(_) => "too many candidates for an exact Kemeny count"
+        }
+    }
+}
+
+impl Display for KemenyError
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result
+    {
+        match *self
+        {
+            KemenyError::TooManyCandidates(n) => write!(f, "too many candidates for an exact Kemeny count ({} > {})", n, MAX_EXACT_CANDIDATES)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod kemeny_tests
+{
+    use super::*;
+    use fixtures;
+
+    #[test]
+    fn tennessee()
+    {
+        let election = fixtures::tennessee();
+
+        let result = Kemeny::with_election(&election).get_result().unwrap();
+
+        assert!(result.is_unique());
+        assert_eq!(result.rankings[0], vec!["Nashville", "Chattanooga", "Knoxville", "Memphis"]);
+        assert_eq!(result.score, Rational::from(393));
+    }
+
+    #[test]
+    fn co_optimal()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        1:A>B>C
+        1:B>C>A
+        1:C>A>B").unwrap();
+
+        let mut result = Kemeny::with_election(&election).get_result().unwrap();
+        result.rankings.sort();
+
+        assert_eq!(result.score, Rational::from(5));
+        assert_eq!(result.rankings, vec![vec!["A", "B", "C"], vec!["B", "C", "A"], vec!["C", "A", "B"]]);
+        assert!(!result.is_unique());
+
+        let result = Kemeny::with_election(&election).max_rankings(2).get_result().unwrap();
+
+        assert_eq!(result.rankings.len(), 2);
+        assert!(!result.complete);
+    }
+
+    #[test]
+    fn fractional_weights()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        1/2:A>B
+        1/3:B>A").unwrap();
+
+        let result = Kemeny::with_election(&election).get_result().unwrap();
+
+        assert_eq!(result.score, Rational::new(1, 2));
+        assert_eq!(result.rankings, vec![vec!["A", "B"]]);
+    }
+}
@@ -0,0 +1,304 @@
+use sum_matrix::SumMatrix;
+use election::Election;
+use ranked_pairs::{RankedPairs, StrengthType};
+use kemeny::integer_matrix;
+use rational::Rational;
+use rng::Rng;
+
+
+/// Where the local search starts from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Seed
+{
+    /// The RankedPairs order. Slower to build for many candidates, but usually close to optimal.
+    RankedPairs(StrengthType),
+    /// Candidates by their total pairwise wins (the Borda count implied by the sum matrix).
+    Borda,
+    /// A given order. Candidates missing from it are added at the bottom, by name.
+    Order(Vec<String>)
+}
+
+/**
+Approximates the Kemeny–Young ranking for elections too large for the exact solver.
+Starting from a seed order, it repeatedly applies the best improving move,
+first swapping adjacent candidates, then moving single candidates to their best position (insertion).
+Once stuck, it perturbs the best order found with a few random moves and searches again, 'restarts' times.
+The same random seed always gives the same result.
+
+The result is measured as a Kemeny distance: the number of voter-pair disagreements, which the Kemeny ranking minimises.
+*/
+pub struct KemenyHeuristic
+{
+    sum_matrix: SumMatrix,
+    seed: Seed,
+    random_seed: u64,
+    restarts: usize
+}
+
+impl KemenyHeuristic
+{
+    pub fn with_matrix(sum_matrix: SumMatrix, seed: Seed) -> Self
+    {
+        KemenyHeuristic{ sum_matrix: sum_matrix, seed: seed, random_seed: 0, restarts: 20 }
+    }
+
+    pub fn with_election(election: &Election, seed: Seed) -> Self
+    {
+        KemenyHeuristic::with_matrix(SumMatrix::new(election), seed)
+    }
+
+    /// Sets the seed for the random perturbations (0 by default).
+    pub fn random_seed(mut self, random_seed: u64) -> Self
+    {
+        self.random_seed = random_seed;
+        self
+    }
+
+    /// Sets how many times the search is restarted from a perturbed order (20 by default).
+    pub fn restarts(mut self, restarts: usize) -> Self
+    {
+        self.restarts = restarts;
+        self
+    }
+
+    fn seed_order(&self, candidates: &[String], weights: &[Vec<i128>]) -> Vec<usize>
+    {
+        let names: Vec<String> = match self.seed
+        {
+            Seed::RankedPairs(strength_type) => {
+                let matrix = self.sum_matrix.restricted(candidates);
+                RankedPairs::with_matrix(matrix, strength_type).get_ranking()
+            },
+            Seed::Borda => {
+                let mut order: Vec<usize> = (0..candidates.len()).collect();
+                order.sort_by_key(|&i| ::std::cmp::Reverse(weights[i].iter().sum::<i128>()));
+                order.into_iter().map(|i| candidates[i].clone()).collect()
+            },
+            Seed::Order(ref order) => order.clone()
+        };
+
+        let mut order: Vec<usize> = Vec::new();
+
+        for i in names.iter().filter_map(|name| candidates.iter().position(|c| c == name)).chain(0..candidates.len())
+        {
+            if !order.contains(&i) { order.push(i); }
+        }
+
+        order
+    }
+
+    /**
+    Gets the best ranking found, its Kemeny distance, and a lower bound on the optimal distance.
+    */
+    pub fn get_result(&self) -> HeuristicResult
+    {
+        let candidates = self.sum_matrix.candidates();
+        let (weights, scale) = integer_matrix(&self.sum_matrix.counts(&candidates));
+
+        let mut best = self.seed_order(&candidates, &weights);
+        local_search(&weights, &mut best);
+        let mut best_cost = cost(&weights, &best);
+
+        let mut rng = Rng::new(self.random_seed);
+
+        for _ in 0..self.restarts
+        {
+            if best.len() < 2 { break; }
+
+            let mut order = best.clone();
+
+            for _ in 0..::std::cmp::max(2, order.len() / 10)
+            {
+                let from = rng.below(order.len());
+                let to = rng.below(order.len());
+                let cand = order.remove(from);
+                order.insert(to, cand);
+            }
+
+            local_search(&weights, &mut order);
+
+            let order_cost = cost(&weights, &order);
+            if order_cost < best_cost
+            {
+                best = order;
+                best_cost = order_cost;
+            }
+        }
+
+        let n = candidates.len();
+        let mut lower_bound = 0;
+        let mut total = 0;
+
+        for i in 0..n
+        {
+            for j in (i + 1)..n
+            {
+                lower_bound += ::std::cmp::min(weights[i][j], weights[j][i]);
+                total += weights[i][j] + weights[j][i];
+            }
+        }
+
+        HeuristicResult
+        {
+            ranking: best.into_iter().map(|i| candidates[i].clone()).collect(),
+            distance: Rational::new(best_cost, scale),
+            lower_bound: Rational::new(lower_bound, scale),
+            agreement: Rational::new(total - best_cost, scale)
+        }
+    }
+}
+
+/// The outcome of a heuristic Kemeny count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeuristicResult
+{
+    /// The best ranking found, best candidate first.
+    pub ranking: Vec<String>,
+    /// Its Kemeny distance: how many voter-pair preferences it disagrees with.
+    pub distance: Rational,
+    /// No ranking can have a smaller distance than this: every pair disagrees with at least its minority.
+    pub lower_bound: Rational,
+    /// How many voter-pair preferences it agrees with (the score the exact solver maximises).
+    pub agreement: Rational
+}
+
+impl HeuristicResult
+{
+    /// How far the distance could be from optimal, at most.
+    pub fn gap(&self) -> Rational
+    {
+        self.distance - self.lower_bound
+    }
+}
+
+/// The Kemeny distance of an order: weights[j][i] for every i placed above j.
+fn cost(weights: &[Vec<i128>], order: &[usize]) -> i128
+{
+    let mut total = 0;
+
+    for (pos, &i) in order.iter().enumerate()
+    {
+        for &j in &order[pos + 1..]
+        {
+            total += weights[j][i];
+        }
+    }
+
+    total
+}
+
+/// Improves 'order' until no adjacent swap or single insertion makes it better.
+fn local_search(weights: &[Vec<i128>], order: &mut Vec<usize>)
+{
+    loop
+    {
+        let mut improved = false;
+
+        // Adjacent swaps.
+        for pos in 1..order.len()
+        {
+            let (above, below) = (order[pos - 1], order[pos]);
+
+            if weights[below][above] > weights[above][below]
+            {
+                order.swap(pos - 1, pos);
+                improved = true;
+            }
+        }
+
+        // Insertions: move each candidate to wherever lowers the distance the most.
+        for from in 0..order.len()
+        {
+            let cand = order[from];
+            let (mut best_to, mut best_change) = (from, 0i128);
+
+            let mut change = 0;
+            for to in (0..from).rev()
+            {
+                let other = order[to];
+                change += weights[other][cand] - weights[cand][other];
+                if change < best_change { best_change = change; best_to = to; }
+            }
+
+            let mut change = 0;
+            for to in (from + 1)..order.len()
+            {
+                let other = order[to];
+                change += weights[cand][other] - weights[other][cand];
+                if change < best_change { best_change = change; best_to = to; }
+            }
+
+            if best_to != from
+            {
+                order.remove(from);
+                order.insert(best_to, cand);
+                improved = true;
+            }
+        }
+
+        if !improved { return; }
+    }
+}
+
+
+#[cfg(test)]
+mod kemeny_heuristic_tests
+{
+    use super::*;
+    use kemeny::Kemeny;
+
+    #[test]
+    fn matches_exact()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        5:A>C>B>E>D
+        5:A>D>E>C>B
+        8:B>E>D>A>C
+        3:C>A>B>E>D
+        7:C>A>E>B>D
+        2:C>B>A>D>E
+        7:D>C>E>B>A
+        8:E>B>A>D>C").unwrap();
+
+        let exact = Kemeny::with_election(&election).get_result().unwrap();
+
+        for seed in vec![Seed::Borda, Seed::RankedPairs(StrengthType::Margin), Seed::Order(vec!["D".to_owned()])]
+        {
+            let result = KemenyHeuristic::with_election(&election, seed).random_seed(3).get_result();
+
+            assert_eq!(result.agreement, exact.score);
+            assert!(exact.rankings.contains(&result.ranking));
+            assert!(result.lower_bound <= result.distance);
+        }
+    }
+
+    #[test]
+    fn many_candidates()
+    {
+        let names: Vec<String> = (0..150).map(|i| format!("C{:03}", i)).collect();
+        let mut rng = Rng::new(11);
+        let mut election = Election::new();
+
+        // Noisy copies of the order C000 > C001 > ...
+        for _ in 0..15
+        {
+            let mut order = names.clone();
+            for _ in 0..40
+            {
+                let i = rng.below(order.len() - 1);
+                order.swap(i, i + 1);
+            }
+            election.add_vote(&order.join(">")).unwrap();
+        }
+
+        let run = || KemenyHeuristic::with_election(&election, Seed::Borda).random_seed(5).restarts(5).get_result();
+        let result = run();
+
+        assert_eq!(result, run());
+        assert!(result.lower_bound <= result.distance);
+        assert_eq!(result.ranking.len(), 150);
+        assert_eq!(result.ranking[0], "C000");
+    }
+}
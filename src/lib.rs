@@ -0,0 +1,40 @@
+pub mod election;
+pub mod sum_matrix;
+pub mod ranked_pairs;
+pub mod matchup;
+pub mod matchup_result;
+pub mod graph;
+pub mod table;
+pub mod rational;
+pub mod ballot_adapters;
+pub mod civs;
+pub mod rng;
+pub mod schulze;
+pub mod schulze_stv;
+pub mod minimax;
+pub mod scores;
+pub mod copeland;
+pub mod ranked_robin;
+pub mod kemeny;
+pub mod kemeny_heuristic;
+pub mod slater;
+pub mod dominance;
+pub mod split_cycle;
+pub mod stable_voting;
+pub mod rounds;
+pub mod borda;
+pub mod simplex;
+pub mod maximal_lottery;
+pub mod method;
+pub mod registry;
+pub mod comparison;
+pub mod irv;
+pub mod dodgson;
+pub mod coombs;
+pub mod bucklin;
+pub mod compose;
+
+pub mod dfs;
+
+#[cfg(test)]
+pub mod fixtures;
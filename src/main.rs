@@ -1,16 +1,11 @@
+extern crate ranked_pairs;
 
-mod election;
-mod sum_matrix;
-mod ranked_pairs;
-mod matchup;
-mod matchup_result;
-mod graph;
-mod table;
+use std::env;
 
-mod dfs;
-
-use election::Election;
-use ranked_pairs::{RankedPairs, StrengthType};
+use ranked_pairs::election::Election;
+use ranked_pairs::method::{Input, Options};
+use ranked_pairs::registry::Registry;
+use ranked_pairs::comparison::Comparison;
 
 
 fn main()
@@ -47,14 +42,26 @@ fn main()
         34:C>S>B
         31:S>B>C").unwrap();
 
-    let ranked_pairs = RankedPairs::with_election(&city_elec, StrengthType::Margin);
+    // The method can be chosen by name, e.g. `cargo run -- schulze`, or `cargo run -- compare` runs them all.
+    let name = env::args().nth(1).unwrap_or_else(|| "ranked-pairs".to_owned());
+    let registry = Registry::default();
+
+    if name == "compare"
+    {
+        print!("{}", Comparison::new(&registry, &city_elec, &Options::new()));
+        return;
+    }
 
-    let winner = match ranked_pairs.get_winner()
+    let outcome = match registry.run(&name, &Input::Election(&city_elec), &Options::new())
     {
-        Ok(w) => w,
+        Ok(o) => o,
         Err(e) => panic!("{}", e)
     };
 
+    for line in &outcome.explanation
+    {
+        println!("{}", line);
+    }
 
-    println!("\nWinner: {}", winner);
+    println!("\nWinner: {}", outcome.winners.join(", "));
 }
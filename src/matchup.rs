@@ -1,5 +1,6 @@
 
 use matchup_result::MatchupResult;
+use rational::Rational;
 
 /**
 A matchup between two candidates, including how many points each has versus the other.
@@ -9,15 +10,15 @@ pub struct Matchup
 {
     first: String,
     second: String,
-    first_wins: u32,
-    second_wins: u32
+    first_wins: Rational,
+    second_wins: Rational
 }
 
 impl Matchup
 {
     pub fn new(first: String, second: String) -> Self
     {
-        Matchup { first: first, second: second, first_wins: 0, second_wins: 0 }
+        Matchup { first: first, second: second, first_wins: Rational::zero(), second_wins: Rational::zero() }
     }
     
     pub fn result<T>(&self, use_margin: T) -> MatchupResult where T: Into<bool>
@@ -34,16 +35,34 @@ impl Matchup
         MatchupResult::new(winner, loser, wins, loses, use_margin.into())
     }
 
-    pub fn add_win_for(&mut self, winner: &str) -> Result<(), MatchupError>
+    /// How many points 'cand' has against the other candidate,
+    /// or None if they aren't in this matchup.
+    pub fn wins_for(&self, cand: &str) -> Option<Rational>
+    {
+        if *cand == self.first
+        {
+            Some(self.first_wins)
+        }
+        else if *cand == self.second
+        {
+            Some(self.second_wins)
+        }
+        else {
+            None
+        }
+    }
+
+    /// Adds 'weight' points for 'winner' against the other candidate.
+    pub fn add_win_for(&mut self, winner: &str, weight: Rational) -> Result<(), MatchupError>
     {
         if *winner == self.first
         {
-            self.first_wins += 1;
+            self.first_wins += weight;
             return Ok(());
         }
         else if *winner == self.second
         {
-            self.second_wins += 1;
+            self.second_wins += weight;
             return Ok(());
         }
         else {
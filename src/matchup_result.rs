@@ -1,6 +1,7 @@
 use std::cmp::Ordering;
 use std::fmt::{self, Formatter, Display};
 use graph::Graph;
+use rational::Rational;
 
 /**
 The results of a matchup, including which candidate won, and how much they won by.
@@ -10,8 +11,8 @@ pub struct MatchupResult<'a>
 {
     winner: &'a str,
     loser: &'a str,
-    wins: &'a u32,
-    loses: &'a u32,
+    wins: &'a Rational,
+    loses: &'a Rational,
     use_margin: bool
 }
 
@@ -63,11 +64,21 @@ impl<'a> PartialOrd for MatchupResult<'a>
 
 impl<'a> MatchupResult<'a>
 {
-    pub fn new(winner: &'a str, loser: &'a str, wins: &'a u32, loses: &'a u32, use_margin: bool) -> Self
+    pub fn new(winner: &'a str, loser: &'a str, wins: &'a Rational, loses: &'a Rational, use_margin: bool) -> Self
     {
         MatchupResult{ winner: winner, loser: loser, wins: wins, loses: loses, use_margin: use_margin }
     }
 
+    pub fn winner(&self) -> &'a str
+    {
+        self.winner
+    }
+
+    pub fn loser(&self) -> &'a str
+    {
+        self.loser
+    }
+
     pub fn try_lock_in(&self, graph: &mut Graph<String>) -> bool
     {
         let is_path = graph.is_path(&self.winner.to_owned(), &self.loser.to_owned());
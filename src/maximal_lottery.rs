@@ -0,0 +1,260 @@
+use sum_matrix::SumMatrix;
+use election::Election;
+use rational::Rational;
+use simplex::{LinearProgram, SimplexError};
+use rng::Rng;
+
+
+/**
+Maximal lotteries (Fishburn) pick the winner at random, from a probability distribution over the candidates
+that no other distribution beats in expectation: for every candidate B, a random candidate drawn from it
+is at least as likely to be preferred to B as B is to it, by margin.
+That's the optimal mixed strategy of the symmetric zero-sum game whose payoffs are the pairwise margins,
+and a Condorcet winner, when there is one, gets all of the probability.
+
+The game is solved exactly as a linear program. With an odd number of voters (more precisely, when no
+margins are zero) the maximal lottery is unique. Otherwise there can be several, and one of them is returned.
+The exact fractions grow quickly with the number of candidates, and with many candidates and large margins
+they can outgrow a Rational, in which case there's no result.
+*/
+pub struct MaximalLottery
+{
+    sum_matrix: SumMatrix
+}
+
+impl MaximalLottery
+{
+    pub fn with_matrix(sum_matrix: SumMatrix) -> Self
+    {
+        MaximalLottery{ sum_matrix: sum_matrix }
+    }
+
+    pub fn with_election(election: &Election) -> Self
+    {
+        MaximalLottery::with_matrix(SumMatrix::new(election))
+    }
+
+    /**
+    Gets the probability of each candidate.
+    */
+    pub fn get_result(&self) -> Result<LotteryResult, LotteryError>
+    {
+        let candidates = self.sum_matrix.candidates();
+        let counts = self.sum_matrix.counts(&candidates);
+        let n = candidates.len();
+
+        if n == 0
+        {
+            return Ok(LotteryResult { probabilities: Vec::new() });
+        }
+
+        let margins: Vec<Vec<Rational>> = (0..n).map(|i| (0..n).map(|j| counts[i][j] - counts[j][i]).collect()).collect();
+
+        // Adding 1 to every payoff makes the game's value 1, so the usual LP for a positive game applies:
+        // maximise the sum of y subject to (margins + 1) y <= 1. Then y / sum(y) is an optimal strategy,
+        // and since the game is symmetric, it's optimal for either player.
+        // Any positive shift would do, and the smallest keeps the fractions in the tableau smallest.
+        let mut lp = LinearProgram::new(vec![Rational::one(); n]);
+        for row in &margins
+        {
+            lp.add_constraint(row.iter().map(|&m| m + Rational::one()).collect(), Rational::one());
+        }
+
+        // Bounded, since the game's value is positive: no y >= 0 makes every payoff non-positive.
+        let solution = match lp.maximize()
+        {
+            Ok(solution) => solution,
+            Err(SimplexError::Overflow) => return Err(LotteryError::Overflow(n)),
+            Err(e) => return Err(LotteryError::Solver(e))
+        };
+        let total = solution.value;
+
+        let mut probabilities = Vec::new();
+        for (cand, y) in candidates.into_iter().zip(solution.values)
+        {
+            probabilities.push((cand, y.checked_div(total).ok_or(LotteryError::Overflow(n))?));
+        }
+
+        Ok(LotteryResult { probabilities: probabilities })
+    }
+}
+
+/// A maximal lottery.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LotteryResult
+{
+    /// Each candidate's chance of winning, by name. They add up to one.
+    pub probabilities: Vec<(String, Rational)>
+}
+
+impl LotteryResult
+{
+    pub fn probability(&self, cand: &str) -> Rational
+    {
+        self.probabilities.iter().find(|&&(ref c, _)| c == cand).map(|&(_, p)| p).unwrap_or_default()
+    }
+
+    /// The candidates with a chance of winning.
+    pub fn support(&self) -> Vec<String>
+    {
+        self.probabilities.iter().filter(|&&(_, p)| p.is_positive()).map(|&(ref c, _)| c.clone()).collect()
+    }
+
+    /// Draws the winner. The same seed always draws the same winner.
+    pub fn draw(&self, seed: u64) -> Option<String>
+    {
+        let weights: Vec<Rational> = self.probabilities.iter().map(|&(_, p)| p).collect();
+
+        Rng::new(seed).choose_weighted(&weights).map(|i| self.probabilities[i].0.clone())
+    }
+}
+
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LotteryError
+{
+    /// The exact arithmetic outgrew a Rational, with this many candidates.
+    Overflow(usize),
+    /// The linear program failed some other way; the game's positive value should rule this out.
+    Solver(SimplexError)
+}
+
+use std::error::Error;
+use std::fmt::{self, Formatter, Display};
+
+impl Error for LotteryError
+{
+    fn description(&self) -> &str
+    {
+        match *self
+        {
+            LotteryError::Overflow(_) => "maximal lottery overflowed",
+            LotteryError::Solver(_) => "maximal lottery program failed"
+        }
+    }
+}
+
+impl Display for LotteryError
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result
+    {
+        match *self
+        {
+            LotteryError::Overflow(n) => write!(f, "maximal lottery overflowed: the exact probabilities for {} candidates are too large to compute", n),
+            LotteryError::Solver(ref e) => write!(f, "maximal lottery program failed: {}", e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod maximal_lottery_tests
+{
+    use super::*;
+    use fixtures;
+
+    #[test]
+    fn condorcet_winner()
+    {
+        let election = fixtures::tennessee();
+
+        let result = MaximalLottery::with_election(&election).get_result().unwrap();
+
+        assert_eq!(result.probability("Nashville"), Rational::one());
+        assert_eq!(result.support(), vec!["Nashville"]);
+        assert_eq!(result.draw(7), Some("Nashville".to_owned()));
+    }
+
+    #[test]
+    fn cycle()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        35:B>C>S
+        34:C>S>B
+        31:S>B>C").unwrap();
+
+        let result = MaximalLottery::with_election(&election).get_result().unwrap();
+
+        // B>C by 32, C>S by 38, S>B by 30: each candidate's chance is the margin of the victory against them
+        // that they don't take part in.
+        assert_eq!(result.probabilities, vec![
+            ("B".to_owned(), Rational::new(38, 100)),
+            ("C".to_owned(), Rational::new(30, 100)),
+            ("S".to_owned(), Rational::new(32, 100))
+        ]);
+
+        let draws: Vec<Option<String>> = (0..20).map(|seed| result.draw(seed)).collect();
+        assert_eq!(draws, (0..20).map(|seed| result.draw(seed)).collect::<Vec<_>>());
+        assert!(draws.iter().all(|d| d.is_some()));
+    }
+
+    #[test]
+    fn dominated_candidate()
+    {
+        let mut election = Election::new();
+
+        // A, B and C form an even cycle, and D loses to all of them.
+        election.add_ballots("
+        1:A>B>C>D
+        1:B>C>A>D
+        1:C>A>B>D").unwrap();
+
+        let result = MaximalLottery::with_election(&election).get_result().unwrap();
+
+        assert_eq!(result.probability("D"), Rational::zero());
+        assert_eq!(result.probability("A"), Rational::new(1, 3));
+        assert_eq!(result.probabilities.iter().map(|&(_, p)| p).sum::<Rational>(), Rational::one());
+    }
+
+    #[test]
+    fn many_candidates()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        588:C>H>E>A>B>D>F>G
+        760:D>A>F>B>C>E>H>G
+        645:D>C>H>A>G>E>F>B
+        134:H>G>B>F>A>D>E>C
+        892:E>F>G>B>H>D>A>C
+        374:H>A>G>E>B>C>F>D
+        65:F>G>E>H>C>A>B>D
+        214:B>G>E>D>H>A>F>C
+        484:F>G>D>H>C>B>A>E").unwrap();
+
+        // This used to overflow the tableau.
+        let result = MaximalLottery::with_election(&election).get_result().unwrap();
+
+        assert_eq!(result.support(), vec!["B", "D", "E", "F", "H"]);
+        assert_eq!(result.probabilities.iter().map(|&(_, p)| p).sum::<Rational>(), Rational::one());
+
+        // No candidate beats the lottery in expectation.
+        let sum_matrix = SumMatrix::new(&election);
+        for &(ref b, _) in &result.probabilities
+        {
+            let margin: Rational = result.probabilities.iter()
+                .map(|&(ref a, p)| p * (sum_matrix.wins(a, b) - sum_matrix.wins(b, a)))
+                .sum();
+
+            assert!(!margin.is_negative());
+        }
+
+        // Scaled up far enough, the exact fractions don't fit, and that's an error rather than a panic.
+        let mut election = Election::new();
+
+        election.add_ballots("
+        588000000001:C>H>E>A>B>D>F>G
+        760000000003:D>A>F>B>C>E>H>G
+        645000000007:D>C>H>A>G>E>F>B
+        134000000009:H>G>B>F>A>D>E>C
+        892000000011:E>F>G>B>H>D>A>C
+        374000000013:H>A>G>E>B>C>F>D
+        65000000017:F>G>E>H>C>A>B>D
+        214000000019:B>G>E>D>H>A>F>C
+        484000000023:F>G>D>H>C>B>A>E").unwrap();
+
+        assert_eq!(MaximalLottery::with_election(&election).get_result(), Err(LotteryError::Overflow(8)));
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use sum_matrix::SumMatrix;
+use election::Election;
+use ranked_pairs::StrengthType;
+use minimax::DefeatScore;
+use borda::Truncation;
+use irv::TieBreak;
+use dominance::DominantSet;
+
+
+/**
+A voting method that can be run from configuration: it takes an Input and string Options,
+and gives a uniform Outcome. The methods in this crate are registered under their names in a Registry,
+and methods from other crates can be added by implementing this trait.
+*/
+pub trait VotingMethod
+{
+    /// The name the method is registered and looked up by, e.g. "ranked-pairs".
+    fn name(&self) -> &str;
+
+    /// Whether the method needs the ballots, rather than just a sum matrix.
+    fn needs_ballots(&self) -> bool
+    {
+        false
+    }
+
+    fn run(&self, input: &Input, options: &Options) -> Result<Outcome, MethodError>;
+}
+
+impl<M: VotingMethod + ?Sized> VotingMethod for &M
+{
+    fn name(&self) -> &str
+    {
+        (**self).name()
+    }
+
+    fn needs_ballots(&self) -> bool
+    {
+        (**self).needs_ballots()
+    }
+
+    fn run(&self, input: &Input, options: &Options) -> Result<Outcome, MethodError>
+    {
+        (**self).run(input, options)
+    }
+}
+
+/// What a method counts: the ballots themselves, or only their pairwise totals.
+#[derive(Clone, Copy)]
+pub enum Input<'a>
+{
+    Election(&'a Election),
+    Matrix(&'a SumMatrix)
+}
+
+impl<'a> Input<'a>
+{
+    /// The sum matrix, counted from the ballots if need be.
+    pub fn sum_matrix(&self) -> SumMatrix
+    {
+        match *self
+        {
+            Input::Election(election) => SumMatrix::new(election),
+            Input::Matrix(sum_matrix) => sum_matrix.clone()
+        }
+    }
+
+    /// The ballots, or a NeedsBallots error naming 'method' if only a sum matrix was given.
+    pub fn election(&self, method: &str) -> Result<&'a Election, MethodError>
+    {
+        match *self
+        {
+            Input::Election(election) => Ok(election),
+            Input::Matrix(_) => Err(MethodError::NeedsBallots(method.to_owned()))
+        }
+    }
+
+    pub fn candidates(&self) -> Vec<String>
+    {
+        match *self
+        {
+            Input::Election(election) => election.candidates(),
+            Input::Matrix(sum_matrix) => sum_matrix.candidates()
+        }
+    }
+
+    /// The same input with only 'candidates' left in it, so that any method can be run on a subset.
+    pub fn restricted(&self, candidates: &[String]) -> RestrictedInput
+    {
+        match *self
+        {
+            Input::Election(election) => RestrictedInput::Election(election.restricted(candidates)),
+            Input::Matrix(sum_matrix) => RestrictedInput::Matrix(sum_matrix.restricted(candidates))
+        }
+    }
+}
+
+/// An Input restricted to some of its candidates. It owns what it holds, so input() lends it out as an Input.
+#[derive(Clone)]
+pub enum RestrictedInput
+{
+    Election(Election),
+    Matrix(SumMatrix)
+}
+
+impl RestrictedInput
+{
+    pub fn input(&self) -> Input<'_>
+    {
+        match *self
+        {
+            RestrictedInput::Election(ref election) => Input::Election(election),
+            RestrictedInput::Matrix(ref sum_matrix) => Input::Matrix(sum_matrix)
+        }
+    }
+}
+
+/**
+Named options, as strings so that they can come straight from a configuration file or the command line.
+Each method reads the ones it understands and ignores the rest. The common ones are:
+
+- strength: "margin" (the default) or "winning-votes"; Minimax also takes "pairwise-opposition"
+- truncation: "average" (the default) or "zero", for methods that give points to positions
+- seed: a number, for methods that draw at random
+- tie-break: "backward" (the default), "all" or "random" (using the seed), for elimination methods and Bucklin
+- set: "smith" (the default) or "schwartz", for methods restricted to a set of undominated candidates
+- seats: a number, for methods that elect a committee (1 by default)
+- sequence: "order" (the default) or "rerun", for how sequential Ranked Pairs fills its seats
+- max-states: a number, limiting exact searches: Dodgson and Young fall back to approximations, and Stable Voting fails
+- max-sets: a number, limiting how many candidate sets Schulze STV compares
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Options
+{
+    values: BTreeMap<String, String>
+}
+
+impl Options
+{
+    pub fn new() -> Self
+    {
+        Options { values: BTreeMap::new() }
+    }
+
+    /// Sets an option, replacing any earlier value.
+    pub fn set(mut self, name: &str, value: &str) -> Self
+    {
+        self.values.insert(name.to_owned(), value.to_owned());
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str>
+    {
+        self.values.get(name).map(|value| &value[..])
+    }
+
+    /// Parses an option, or gives 'default' if it isn't set.
+    pub fn parse<T: FromStr>(&self, name: &str, default: T) -> Result<T, MethodError>
+    {
+        match self.get(name)
+        {
+            Some(value) => value.parse().map_err(|_| self.invalid(name)),
+            None => Ok(default)
+        }
+    }
+
+    /// The "strength" option.
+    pub fn strength_type(&self) -> Result<StrengthType, MethodError>
+    {
+        match self.get("strength")
+        {
+            None | Some("margin") => Ok(StrengthType::Margin),
+            Some("winning-votes") => Ok(StrengthType::WinningVotes),
+            Some(_) => Err(self.invalid("strength"))
+        }
+    }
+
+    /// The "strength" option, for Minimax.
+    pub fn defeat_score(&self) -> Result<DefeatScore, MethodError>
+    {
+        match self.get("strength")
+        {
+            Some("pairwise-opposition") => Ok(DefeatScore::PairwiseOpposition),
+            _ => self.strength_type().map(DefeatScore::Strength)
+        }
+    }
+
+    /// The "truncation" option.
+    pub fn truncation(&self) -> Result<Truncation, MethodError>
+    {
+        match self.get("truncation")
+        {
+            None | Some("average") => Ok(Truncation::Average),
+            Some("zero") => Ok(Truncation::Zero),
+            Some(_) => Err(self.invalid("truncation"))
+        }
+    }
+
+    /// The "tie-break" option.
+    pub fn tie_break(&self) -> Result<TieBreak, MethodError>
+    {
+        match self.get("tie-break")
+        {
+            None | Some("backward") => Ok(TieBreak::Backward),
+            Some("all") => Ok(TieBreak::All),
+            Some("random") => Ok(TieBreak::Random(self.parse("seed", 0)?)),
+            Some(_) => Err(self.invalid("tie-break"))
+        }
+    }
+
+    /// The "set" option.
+    pub fn dominant_set(&self) -> Result<DominantSet, MethodError>
+    {
+        match self.get("set")
+        {
+            None | Some("smith") => Ok(DominantSet::Smith),
+            Some("schwartz") => Ok(DominantSet::Schwartz),
+            Some(_) => Err(self.invalid("set"))
+        }
+    }
+
+    fn invalid(&self, name: &str) -> MethodError
+    {
+        MethodError::InvalidOption { name: name.to_owned(), value: self.get(name).unwrap_or("").to_owned() }
+    }
+}
+
+/// The result of running a VotingMethod.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome
+{
+    /// Usually one candidate, but several if the method ties.
+    pub winners: Vec<String>,
+    /// Every candidate, best first; candidates the method can't separate share a rank.
+    pub ranking: Vec<Vec<String>>,
+    /// How the method reached its result, one line at a time, for people to read.
+    pub explanation: Vec<String>
+}
+
+impl Outcome
+{
+    /// An outcome whose winners are the first rank.
+    pub fn from_ranking(ranking: Vec<Vec<String>>, explanation: Vec<String>) -> Self
+    {
+        Outcome { winners: ranking.first().cloned().unwrap_or_default(), ranking: ranking, explanation: explanation }
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MethodError
+{
+    /// No method is registered under this name.
+    UnknownMethod(String),
+    /// The named method was given only a sum matrix.
+    NeedsBallots(String),
+    InvalidOption { name: String, value: String },
+    /// The method couldn't give a result, e.g. because the election is too large for it.
+    Failed(String)
+}
+
+use std::error::Error;
+use std::fmt::{self, Formatter, Display};
+
+impl Error for MethodError
+{
+    fn description(&self) -> &str
+    {
+        match *self
+        {
+            MethodError::UnknownMethod(_) => "unknown voting method",
+            MethodError::NeedsBallots(_) => "voting method needs ballots, not a sum matrix",
+            MethodError::InvalidOption { .. } => "invalid option",
+            MethodError::Failed(_) => "voting method failed"
+        }
+    }
+}
+
+impl Display for MethodError
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result
+    {
+        match *self
+        {
+            MethodError::UnknownMethod(ref name) => write!(f, "unknown voting method \"{}\"", name),
+            MethodError::NeedsBallots(ref name) => write!(f, "{} needs ballots, not a sum matrix", name),
+            MethodError::InvalidOption { ref name, ref value } => write!(f, "invalid value \"{}\" for option {}", value, name),
+            MethodError::Failed(ref reason) => write!(f, "{}", reason)
+        }
+    }
+}
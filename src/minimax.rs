@@ -0,0 +1,181 @@
+use sum_matrix::SumMatrix;
+use election::Election;
+use ranked_pairs::StrengthType;
+use rational::Rational;
+use scores::rank_by;
+use std::cmp::Reverse;
+
+
+/**
+Minimax (Simpson–Kramer) elects the candidate whose worst pairwise defeat is the least bad,
+where how bad a defeat is depends on the DefeatScore.
+*/
+pub struct Minimax
+{
+    sum_matrix: SumMatrix,
+    defeat_score: DefeatScore
+}
+
+/**
+How Minimax scores a defeat: the strength of the opponent's victory, as for RankedPairs
+(a margin can be negative, when the candidate wins; winning votes are nothing then),
+or PairwiseOpposition, which has no counterpart there since it scores matchups the candidate wins as well.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefeatScore
+{
+    Strength(StrengthType),
+    /// The opponent's votes, whether or not the opponent wins the matchup.
+    PairwiseOpposition
+}
+
+impl From<StrengthType> for DefeatScore
+{
+    fn from(strength_type: StrengthType) -> Self
+    {
+        DefeatScore::Strength(strength_type)
+    }
+}
+
+impl Minimax
+{
+    pub fn with_matrix<D: Into<DefeatScore>>(sum_matrix: SumMatrix, defeat_score: D) -> Self
+    {
+        Minimax{ sum_matrix: sum_matrix, defeat_score: defeat_score.into() }
+    }
+
+    pub fn with_election<D: Into<DefeatScore>>(election: &Election, defeat_score: D) -> Self
+    {
+        Minimax::with_matrix(SumMatrix::new(election), defeat_score)
+    }
+
+    /// How bad it is for a candidate to have 'against' votes against them and 'support' for them in a matchup.
+    fn defeat_score(&self, against: Rational, support: Rational) -> Rational
+    {
+        match self.defeat_score
+        {
+            DefeatScore::Strength(strength_type) if strength_type.use_margin() || against > support => strength_type.strength(against, support),
+            DefeatScore::Strength(_) => Rational::zero(),
+            DefeatScore::PairwiseOpposition => against
+        }
+    }
+
+    /**
+    Gets every candidate's worst defeat and the Minimax order.
+    */
+    pub fn get_result(&self) -> MinimaxResult
+    {
+        let candidates = self.sum_matrix.candidates();
+        let counts = self.sum_matrix.counts(&candidates);
+        let n = candidates.len();
+
+        let mut worst_defeats: Vec<WorstDefeat> = Vec::new();
+
+        for c in 0..n
+        {
+            let mut score = None;
+            let mut opponents = Vec::new();
+
+            for x in (0..n).filter(|&x| x != c)
+            {
+                let this = self.defeat_score(counts[x][c], counts[c][x]);
+
+                if score.map_or(true, |s| this > s)
+                {
+                    score = Some(this);
+                    opponents.clear();
+                }
+                if score == Some(this)
+                {
+                    opponents.push(candidates[x].clone());
+                }
+            }
+
+            // A lone candidate, or a WinningVotes score with no defeats, has no one to name.
+            let score = score.unwrap_or_default();
+            if self.defeat_score == DefeatScore::Strength(StrengthType::WinningVotes) && score.is_zero()
+            {
+                opponents.clear();
+            }
+
+            worst_defeats.push(WorstDefeat { candidate: candidates[c].clone(), score: score, opponents: opponents });
+        }
+
+        worst_defeats.sort_by(|a, b| a.score.cmp(&b.score).then_with(|| a.candidate.cmp(&b.candidate)));
+
+        let ranking = rank_by(worst_defeats.iter().map(|d| (d.candidate.clone(), Reverse(d.score))).collect());
+
+        let winners = ranking.first().cloned().unwrap_or_default();
+
+        MinimaxResult { worst_defeats: worst_defeats, winners: winners, ranking: ranking }
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+/// A candidate's worst pairwise result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorstDefeat
+{
+    pub candidate: String,
+    pub score: Rational,
+    /// The opponents who inflict it. Empty when scoring by WinningVotes and the candidate is undefeated.
+    pub opponents: Vec<String>
+}
+
+/// The outcome of a Minimax count.
+pub struct MinimaxResult
+{
+    /// Every candidate's worst defeat, best (lowest score) first.
+    pub worst_defeats: Vec<WorstDefeat>,
+    pub winners: Vec<String>,
+    /// Candidates grouped by score, best first.
+    pub ranking: Vec<Vec<String>>
+}
+
+
+#[cfg(test)]
+mod minimax_tests
+{
+    use super::*;
+
+    // The variants disagree on this one.
+    fn example() -> Election
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        8:A=C>B
+        8:A=B>C
+        6:C>B>A
+        5:B>A=C").unwrap();
+
+        election
+    }
+
+    #[test]
+    fn variants()
+    {
+        let winners = |defeat_score: DefeatScore| Minimax::with_election(&example(), defeat_score).get_winners();
+
+        assert_eq!(winners(StrengthType::WinningVotes.into()), vec!["C"]);
+        assert_eq!(winners(StrengthType::Margin.into()), vec!["B"]);
+        assert_eq!(winners(DefeatScore::PairwiseOpposition), vec!["A"]);
+    }
+
+    #[test]
+    fn worst_defeats()
+    {
+        let result = Minimax::with_election(&example(), StrengthType::WinningVotes).get_result();
+
+        assert_eq!(result.worst_defeats[0], WorstDefeat { candidate: "C".to_owned(), score: 8.into(), opponents: vec!["A".to_owned()] });
+        assert_eq!(result.ranking, vec![vec!["C"], vec!["A"], vec!["B"]]);
+
+        let result = Minimax::with_election(&example(), StrengthType::Margin).get_result();
+
+        assert_eq!(result.worst_defeats[0], WorstDefeat { candidate: "B".to_owned(), score: 1.into(), opponents: vec!["C".to_owned()] });
+    }
+}
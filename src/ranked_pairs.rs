@@ -3,6 +3,9 @@ use sum_matrix::SumMatrix;
 use graph::{EmptyGraphError, Graph};
 use election::Election;
 use matchup_result::MatchupResult;
+use rational::Rational;
+use std::collections::HashMap;
+use std::str::FromStr;
 
 
 /**
@@ -73,8 +76,213 @@ impl RankedPairs
 
         graph.find_sink()
     }
+
+    /**
+    Gets the full RankedPairs order, winner first.
+    Every pair is locked in (or skipped) exactly as for get_winner(), so the locked victories order everyone.
+    */
+    pub fn get_ranking(&self) -> Vec<String>
+    {
+        let mut graph = Graph::new();
+        let mut beaten_by: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut unbeaten: HashMap<&str, usize> = HashMap::new();
+
+        let candidates = self.sum_matrix.candidates();
+
+        for cand in &candidates
+        {
+            unbeaten.insert(cand, 0);
+        }
+
+        let ranked_pairs = self.ranked_pairs();
+
+        for result in &ranked_pairs
+        {
+            if result.try_lock_in(&mut graph)
+            {
+                beaten_by.entry(result.winner()).or_insert_with(Vec::new).push(result.loser());
+                *unbeaten.get_mut(result.loser()).unwrap() += 1;
+            }
+        }
+
+        // Repeatedly take whoever no remaining candidate beats.
+        let mut ranking = Vec::new();
+        // Kept in reverse order, so that ties go to the alphabetically first.
+        let mut ready: Vec<&str> = candidates.iter().map(|c| &c[..]).filter(|c| unbeaten[c] == 0).collect();
+        ready.sort_by(|a, b| b.cmp(a));
+
+        while let Some(cand) = ready.pop()
+        {
+            ranking.push(cand.to_owned());
+
+            for loser in beaten_by.get(cand).map(|l| &l[..]).unwrap_or(&[])
+            {
+                let count = unbeaten.get_mut(loser).unwrap();
+                *count -= 1;
+
+                if *count == 0
+                {
+                    ready.push(loser);
+                }
+            }
+
+            ready.sort_by(|a, b| b.cmp(a));
+        }
+
+        ranking
+    }
+
+    /**
+    Gets the RankedPairs winner along with the Smith and Schwartz sets, so audits can check the winner is in both.
+    */
+    pub fn get_compliance(&self) -> Result<SetCompliance, EmptyGraphError>
+    {
+        Ok(SetCompliance
+        {
+            winner: self.get_winner()?,
+            smith_set: self.sum_matrix.smith_set(),
+            schwartz_components: self.sum_matrix.schwartz_components()
+        })
+    }
+}
+
+/// The RankedPairs winner next to the sets a Condorcet method's winner should come from.
+pub struct SetCompliance
+{
+    pub winner: String,
+    pub smith_set: Vec<String>,
+    /// Each minimal undominated set of the Schwartz set.
+    pub schwartz_components: Vec<Vec<String>>
+}
+
+impl SetCompliance
+{
+    pub fn in_smith_set(&self) -> bool
+    {
+        self.smith_set.contains(&self.winner)
+    }
+
+    pub fn in_schwartz_set(&self) -> bool
+    {
+        self.schwartz_components.iter().any(|component| component.contains(&self.winner))
+    }
+}
+
+/// How SequentialRankedPairs fills its seats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sequence
+{
+    /// Take the top of the RankedPairs order, from a single count.
+    Order,
+    /// Take the RankedPairs winner, then count again without them for each seat after.
+    Rerun
+}
+
+impl FromStr for Sequence
+{
+    type Err = ();
+
+    /// Parses "order" or "rerun".
+    fn from_str(s: &str) -> Result<Self, ()>
+    {
+        match s
+        {
+            "order" => Ok(Sequence::Order),
+            "rerun" => Ok(Sequence::Rerun),
+            _ => Err(())
+        }
+    }
+}
+
+/**
+Elects a committee by Ranked Pairs: either the first 'seats' candidates of the RankedPairs order,
+or by electing the RankedPairs winner and running RankedPairs again on the candidates left for each seat after.
+Ranked Pairs satisfies local independence of irrelevant alternatives, so the two only differ when
+victories of equal strength are locked in a different order once a winner is removed.
+This isn't proportional: a majority faction can take every seat.
+*/
+pub struct SequentialRankedPairs
+{
+    sum_matrix: SumMatrix,
+    strength_type: StrengthType,
+    seats: usize,
+    sequence: Sequence
 }
 
+impl SequentialRankedPairs
+{
+    pub fn with_matrix(sum_matrix: SumMatrix, strength_type: StrengthType, seats: usize) -> Self
+    {
+        SequentialRankedPairs{ sum_matrix: sum_matrix, strength_type: strength_type, seats: seats, sequence: Sequence::Order }
+    }
+
+    pub fn with_election(election: &Election, strength_type: StrengthType, seats: usize) -> Self
+    {
+        SequentialRankedPairs::with_matrix(SumMatrix::new(election), strength_type, seats)
+    }
+
+    /// Sets how the seats are filled (Sequence::Order by default).
+    pub fn sequence(mut self, sequence: Sequence) -> Self
+    {
+        self.sequence = sequence;
+        self
+    }
+
+    /**
+    Gets each seat's winner and how they were chosen.
+    If there are fewer candidates than seats, every candidate gets a seat and the rest stay empty.
+    */
+    pub fn get_result(&self) -> Vec<Seat>
+    {
+        let mut seats = Vec::new();
+
+        match self.sequence
+        {
+            Sequence::Order => {
+                let ranking = RankedPairs::with_matrix(self.sum_matrix.clone(), self.strength_type).get_ranking();
+                let order = ranking.join(" > ");
+
+                for (i, winner) in ranking.into_iter().take(self.seats).enumerate()
+                {
+                    let explanation = format!("Place {} in the locked order {}", i + 1, order);
+                    seats.push(Seat { winner: winner, explanation: explanation });
+                }
+            },
+            Sequence::Rerun => {
+                let mut remaining = self.sum_matrix.candidates();
+
+                while seats.len() < self.seats && !remaining.is_empty()
+                {
+                    let ranking = RankedPairs::with_matrix(self.sum_matrix.restricted(&remaining), self.strength_type).get_ranking();
+                    let winner = ranking[0].clone();
+                    let explanation = format!("Wins the locked order of those left, {}", ranking.join(" > "));
+
+                    remaining.retain(|c| *c != winner);
+                    seats.push(Seat { winner: winner, explanation: explanation });
+                }
+            }
+        }
+
+        seats
+    }
+
+    /// The winners, in the order they got their seats.
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().into_iter().map(|seat| seat.winner).collect()
+    }
+}
+
+/// One seat of a committee, and why its winner got it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seat
+{
+    pub winner: String,
+    pub explanation: String
+}
+
+/// How the strength of a pairwise victory is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StrengthType
 {
     Margin,
@@ -91,6 +299,16 @@ impl StrengthType
             StrengthType::WinningVotes => false
         }
     }
+
+    /// The strength of a victory with 'wins' votes for and 'loses' votes against.
+    pub fn strength(&self, wins: Rational, loses: Rational) -> Rational
+    {
+        match *self
+        {
+            StrengthType::Margin => wins - loses,
+            StrengthType::WinningVotes => wins
+        }
+    }
 }
 
 impl<'a> From<&'a StrengthType> for bool
@@ -110,18 +328,13 @@ impl<'a> From<&'a StrengthType> for bool
 mod RankedPairsTests
 {
     use super::*;
+    use fixtures;
 
     #[test]
     /// Winner should be Nashville?
     fn test_one()
     {
-        let mut election = Election::new();
-
-        election.add_ballots("
-        42:Memphis>Nashville>Chattanooga>Knoxville
-        26:Nashville>Chattanooga>Knoxville>Memphis
-        15:Chattanooga>Knoxville>Nashville>Memphis
-        17:Knoxville>Chattanooga>Nashville>Memphis").unwrap();
+        let election = fixtures::tennessee();
 
         let ranked_pairs = RankedPairs::with_election(&election, StrengthType::Margin);
 
@@ -178,5 +391,138 @@ mod RankedPairsTests
             Err(e) => { panic!("Error: {}", e) }
         }
     }
-}
 
+    #[test]
+    fn test_ranking()
+    {
+        let election = fixtures::tennessee();
+
+        let ranked_pairs = RankedPairs::with_election(&election, StrengthType::Margin);
+
+        assert_eq!(ranked_pairs.get_ranking(), vec!["Nashville", "Chattanooga", "Knoxville", "Memphis"]);
+    }
+
+    #[test]
+    fn test_ranking_ties()
+    {
+        let mut election = Election::new();
+
+        // Nobody compares A with B or C, so A and B are both unbeaten, and the tie goes to A.
+        election.add_ballots("
+        2:B>C
+        1:A").unwrap();
+
+        let ranked_pairs = RankedPairs::with_election(&election, StrengthType::Margin);
+
+        assert_eq!(ranked_pairs.get_ranking(), vec!["A", "B", "C"]);
+
+        let seats = SequentialRankedPairs::with_election(&election, StrengthType::Margin, 2).get_winners();
+        assert_eq!(seats, vec!["A", "B"]);
+    }
+
+    #[test]
+    fn test_committee_truncated()
+    {
+        let mut election = Election::new();
+
+        // Nobody compares D with A or B, so D is only beaten if one of those pairs is locked in as a 0-0 tie.
+        election.add_ballots("
+        5:A>B
+        3:B>C
+        1:C>A
+        2:D>C").unwrap();
+
+        let order = SequentialRankedPairs::with_election(&election, StrengthType::Margin, 3).get_winners();
+        let rerun = SequentialRankedPairs::with_election(&election, StrengthType::Margin, 3).sequence(Sequence::Rerun).get_winners();
+
+        assert_eq!(order, &RankedPairs::with_election(&election, StrengthType::Margin).get_ranking()[..3]);
+        assert_eq!(order, vec!["A", "B", "D"]);
+        assert_eq!(rerun, order);
+    }
+
+    #[test]
+    fn test_committee()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        5:A>C>B>E>D
+        5:A>D>E>C>B
+        8:B>E>D>A>C
+        3:C>A>B>E>D
+        7:C>A>E>B>D
+        2:C>B>A>D>E
+        7:D>C>E>B>A
+        8:E>B>A>D>C").unwrap();
+
+        let order = SequentialRankedPairs::with_election(&election, StrengthType::Margin, 3).get_result();
+        let rerun = SequentialRankedPairs::with_election(&election, StrengthType::Margin, 3).sequence(Sequence::Rerun).get_result();
+
+        let ranking = RankedPairs::with_election(&election, StrengthType::Margin).get_ranking();
+        let winners: Vec<String> = order.iter().map(|seat| seat.winner.clone()).collect();
+
+        assert_eq!(winners, &ranking[..3]);
+        assert_eq!(rerun.iter().map(|seat| seat.winner.clone()).collect::<Vec<_>>(), winners);
+        assert_eq!(order[1].explanation, format!("Place 2 in the locked order {}", ranking.join(" > ")));
+        assert_eq!(rerun[2].explanation, format!("Wins the locked order of those left, {}", ranking[2..].join(" > ")));
+    }
+
+    #[test]
+    fn test_more_seats_than_candidates()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        35:B>C>S
+        34:C>S>B
+        31:S>B>C").unwrap();
+
+        for sequence in vec![Sequence::Order, Sequence::Rerun]
+        {
+            let committee = SequentialRankedPairs::with_election(&election, StrengthType::Margin, 5).sequence(sequence);
+            assert_eq!(committee.get_winners(), vec!["B", "C", "S"]);
+        }
+
+        assert_eq!("rerun".parse(), Ok(Sequence::Rerun));
+        assert!("random".parse::<Sequence>().is_err());
+    }
+
+    #[test]
+    fn test_compliance()
+    {
+        let mut election = Election::new();
+
+
+        election.add_ballots("
+        35:B>C>S
+        34:C>S>B
+        31:S>B>C").unwrap();
+
+        let compliance = RankedPairs::with_election(&election, StrengthType::Margin).get_compliance().unwrap();
+
+        assert_eq!(compliance.smith_set, vec!["B", "C", "S"]);
+        assert!(compliance.in_smith_set());
+        assert!(compliance.in_schwartz_set());
+    }
+
+    #[test]
+    // Same as test_three, with the amounts scaled down to fractions.
+    fn test_fractional()
+    {
+        let mut election = Election::new();
+
+
+        election.add_ballots("
+        0.35:B>C>S
+        34/100:C>S>B
+        0.31:S>B>C").unwrap();
+
+        let ranked_pairs = RankedPairs::with_election(&election, StrengthType::WinningVotes);
+
+        match ranked_pairs.get_winner()
+        {
+            Ok(winner) => assert_eq!(winner, "B", "Result ({}) != B", winner),
+            Err(e) => { panic!("Error: {}", e) }
+        }
+    }
+}
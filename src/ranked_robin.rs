@@ -0,0 +1,171 @@
+use sum_matrix::SumMatrix;
+use election::Election;
+use rational::Rational;
+use scores::rank_by;
+
+
+/**
+Ranked Robin ranks candidates by how many head-to-head matchups they win, as Copeland does with ties worth nothing.
+Candidates with the same number of wins are separated by their wins against each other;
+if that doesn't separate them, by their total margin (the votes they get over every opponent, minus the votes
+against them, which is the Borda count on complete ballots). Whenever a tie-breaker splits a group,
+the tie-breakers start again on each part that's still tied. Candidates still tied after both share a rank.
+*/
+pub struct RankedRobin
+{
+    sum_matrix: SumMatrix
+}
+
+impl RankedRobin
+{
+    pub fn with_matrix(sum_matrix: SumMatrix) -> Self
+    {
+        RankedRobin{ sum_matrix: sum_matrix }
+    }
+
+    pub fn with_election(election: &Election) -> Self
+    {
+        RankedRobin::with_matrix(SumMatrix::new(election))
+    }
+
+    /// How many of 'opponents' 'cand' beats.
+    fn wins(&self, cand: &str, opponents: &[String]) -> usize
+    {
+        opponents.iter().filter(|&o| self.sum_matrix.wins(cand, o) > self.sum_matrix.wins(o, cand)).count()
+    }
+
+    /// Orders a group tied on wins, using the tie-breakers.
+    fn break_ties(&self, group: Vec<String>, margins: &[(String, Rational)]) -> Vec<Vec<String>>
+    {
+        if group.len() < 2
+        {
+            return vec![group];
+        }
+
+        let head_to_head = rank_by(group.iter().map(|c| (c.clone(), self.wins(c, &group))).collect());
+
+        let parts = if head_to_head.len() > 1
+        {
+            head_to_head
+        }
+        else {
+            rank_by(margins.iter().filter(|&&(ref c, _)| group.contains(c)).cloned().collect())
+        };
+
+        if parts.len() == 1
+        {
+            return parts;
+        }
+
+        parts.into_iter().flat_map(|part| self.break_ties(part, margins)).collect()
+    }
+
+    pub fn get_result(&self) -> RankedRobinResult
+    {
+        let candidates = self.sum_matrix.candidates();
+
+        let wins: Vec<(String, usize)> = candidates.iter().map(|c| (c.clone(), self.wins(c, &candidates))).collect();
+
+        let margins: Vec<(String, Rational)> = candidates.iter()
+            .map(|c| {
+                let margin = candidates.iter().map(|o| self.sum_matrix.wins(c, o) - self.sum_matrix.wins(o, c)).sum();
+                (c.clone(), margin)
+            })
+            .collect();
+
+        let by_wins = rank_by(wins.clone());
+        let ties: Vec<Vec<String>> = by_wins.iter().filter(|rank| rank.len() > 1).cloned().collect();
+
+        let ranking: Vec<Vec<String>> = by_wins.into_iter().flat_map(|rank| self.break_ties(rank, &margins)).collect();
+        let winners = ranking.first().cloned().unwrap_or_default();
+
+        RankedRobinResult { wins: wins, margins: margins, ranking: ranking, winners: winners, ties: ties }
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+/// The outcome of a Ranked Robin count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankedRobinResult
+{
+    /// How many matchups each candidate wins, by candidate name.
+    pub wins: Vec<(String, usize)>,
+    /// Each candidate's total margin over everyone else, by candidate name.
+    pub margins: Vec<(String, Rational)>,
+    /// Best first, after the tie-breakers. Candidates they couldn't separate share a rank.
+    pub ranking: Vec<Vec<String>>,
+    pub winners: Vec<String>,
+    /// Groups of candidates with the same number of wins, before any tie-breaking.
+    pub ties: Vec<Vec<String>>
+}
+
+
+#[cfg(test)]
+mod ranked_robin_tests
+{
+    use super::*;
+    use fixtures;
+
+    #[test]
+    fn tennessee()
+    {
+        let election = fixtures::tennessee();
+
+        let result = RankedRobin::with_election(&election).get_result();
+
+        assert_eq!(result.winners, vec!["Nashville"]);
+        assert_eq!(result.ranking, vec![vec!["Nashville"], vec!["Chattanooga"], vec!["Knoxville"], vec!["Memphis"]]);
+        assert!(result.ties.is_empty());
+    }
+
+    #[test]
+    fn head_to_head()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        4:C>B>D>A
+        2:A>C>D>B
+        5:B>D>A>C").unwrap();
+
+        let result = RankedRobin::with_election(&election).get_result();
+
+        // B and C both win twice, and B has the bigger margin, but C beats B 6-5. Then D beats A.
+        assert_eq!(result.ties, vec![vec!["B", "C"], vec!["A", "D"]]);
+        assert_eq!(result.margins[1], ("B".to_owned(), 13.into()));
+        assert_eq!(result.ranking, vec![vec!["C"], vec!["B"], vec!["D"], vec!["A"]]);
+    }
+
+    #[test]
+    fn margins()
+    {
+        let mut election = Election::new();
+
+        // A, B and C beat each other in a cycle and all beat D. A and B tie on margin, and then A beats B.
+        election.add_ballots("
+        4:A>B>C>D
+        3:B>C>A>D
+        2:C>A>B>D").unwrap();
+
+        let result = RankedRobin::with_election(&election).get_result();
+
+        assert_eq!(result.ties, vec![vec!["A", "B", "C"]]);
+        assert_eq!(result.margins[0], ("A".to_owned(), 11.into()));
+        assert_eq!(result.margins[1], ("B".to_owned(), 11.into()));
+        assert_eq!(result.ranking, vec![vec!["A"], vec!["B"], vec!["C"], vec!["D"]]);
+
+        let mut election = Election::new();
+
+        election.add_ballots("
+        1:A>B>C
+        1:B>C>A
+        1:C>A>B").unwrap();
+
+        // Nothing separates a perfect cycle.
+        assert_eq!(RankedRobin::with_election(&election).get_result().ranking, vec![vec!["A", "B", "C"]]);
+    }
+}
@@ -0,0 +1,485 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Formatter, Display};
+use std::iter::Sum;
+use std::ops::{Add, Sub, Mul, Div, Neg, AddAssign, SubAssign};
+use std::str::FromStr;
+
+/**
+An exact fraction, always stored in lowest terms with a positive denominator.
+Used for ballot weights and tallies, so that ties and margins are never distorted by rounding.
+Arithmetic panics if a result no longer fits, rather than silently losing precision.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational
+{
+    num: i128,
+    den: i128
+}
+
+fn gcd(a: i128, b: i128) -> i128
+{
+    let (mut a, mut b) = (a.abs(), b.abs());
+
+    while b != 0
+    {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+
+    a
+}
+
+fn checked<T>(value: Option<T>) -> T
+{
+    match value
+    {
+        Some(value) => value,
+        None => panic!("rational arithmetic overflowed")
+    }
+}
+
+impl Rational
+{
+    /// Creates num/den in lowest terms.
+    /// Panics if 'den' is zero.
+    pub fn new(num: i128, den: i128) -> Self
+    {
+        if den == 0
+        {
+            panic!("rational with a zero denominator");
+        }
+
+        let g = gcd(num, den);
+        let (num, den) = if den < 0 { (-num / g, -den / g) } else { (num / g, den / g) };
+
+        Rational { num: num, den: den }
+    }
+
+    pub fn zero() -> Self
+    {
+        Rational { num: 0, den: 1 }
+    }
+
+    pub fn one() -> Self
+    {
+        Rational { num: 1, den: 1 }
+    }
+
+    pub fn numer(&self) -> i128
+    {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128
+    {
+        self.den
+    }
+
+    pub fn is_zero(&self) -> bool
+    {
+        self.num == 0
+    }
+
+    pub fn is_positive(&self) -> bool
+    {
+        self.num > 0
+    }
+
+    pub fn is_negative(&self) -> bool
+    {
+        self.num < 0
+    }
+
+    pub fn is_integer(&self) -> bool
+    {
+        self.den == 1
+    }
+
+    pub fn abs(&self) -> Self
+    {
+        Rational { num: self.num.abs(), den: self.den }
+    }
+
+    /// The largest integer not greater than this.
+    pub fn floor(&self) -> i128
+    {
+        self.num.div_euclid(self.den)
+    }
+
+    /// The smallest integer not less than this.
+    pub fn ceil(&self) -> i128
+    {
+        -(-self.num).div_euclid(self.den)
+    }
+
+    /// Adds, or returns None if the result doesn't fit.
+    pub fn checked_add(self, other: Rational) -> Option<Rational>
+    {
+        let g = gcd(self.den, other.den);
+        let left = self.num.checked_mul(other.den / g)?;
+        let right = other.num.checked_mul(self.den / g)?;
+
+        Some(Rational::new(left.checked_add(right)?, (self.den / g).checked_mul(other.den)?))
+    }
+
+    /// Subtracts, or returns None if the result doesn't fit.
+    pub fn checked_sub(self, other: Rational) -> Option<Rational>
+    {
+        self.checked_add(-other)
+    }
+
+    /// Multiplies, or returns None if the result doesn't fit.
+    pub fn checked_mul(self, other: Rational) -> Option<Rational>
+    {
+        // Cross-reducing first keeps the intermediate products small.
+        let g1 = gcd(self.num, other.den);
+        let g2 = gcd(other.num, self.den);
+        let (g1, g2) = (if g1 == 0 { 1 } else { g1 }, if g2 == 0 { 1 } else { g2 });
+
+        let num = (self.num / g1).checked_mul(other.num / g2)?;
+        let den = (self.den / g2).checked_mul(other.den / g1)?;
+
+        Some(Rational::new(num, den))
+    }
+
+    /// Divides, or returns None if the result doesn't fit or 'other' is zero.
+    pub fn checked_div(self, other: Rational) -> Option<Rational>
+    {
+        if other.num == 0
+        {
+            return None;
+        }
+
+        self.checked_mul(Rational::new(other.den, other.num))
+    }
+
+    /// Approximates the value, for display or reporting only.
+    pub fn to_f64(&self) -> f64
+    {
+        self.num as f64 / self.den as f64
+    }
+}
+
+impl Default for Rational
+{
+    fn default() -> Self
+    {
+        Rational::zero()
+    }
+}
+
+macro_rules! from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Rational
+            {
+                fn from(n: $t) -> Self
+                {
+                    Rational { num: n as i128, den: 1 }
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(i32, u32, i64, u64, usize);
+
+impl Ord for Rational
+{
+    fn cmp(&self, other: &Rational) -> Ordering
+    {
+        // Compares the continued fractions, so that nothing is multiplied and nothing can overflow:
+        // first the integer parts, then (the other way round) the reciprocals of what's left.
+        let (mut a, mut b) = (*self, *other);
+        let mut reversed = false;
+
+        loop
+        {
+            let (floor_a, floor_b) = (a.floor(), b.floor());
+            let (rem_a, rem_b) = (a.num - floor_a * a.den, b.num - floor_b * b.den);
+
+            let ordering = floor_a.cmp(&floor_b).then_with(|| match (rem_a, rem_b)
+            {
+                (0, 0) => Ordering::Equal,
+                (0, _) => Ordering::Less,
+                (_, 0) => Ordering::Greater,
+                _ => Ordering::Equal
+            });
+
+            if ordering != Ordering::Equal || rem_a == 0
+            {
+                return if reversed { ordering.reverse() } else { ordering };
+            }
+
+            a = Rational { num: a.den, den: rem_a };
+            b = Rational { num: b.den, den: rem_b };
+            reversed = !reversed;
+        }
+    }
+}
+
+impl PartialOrd for Rational
+{
+    fn partial_cmp(&self, other: &Rational) -> Option<Ordering>
+    {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Rational
+{
+    type Output = Rational;
+
+    fn add(self, other: Rational) -> Rational
+    {
+        checked(self.checked_add(other))
+    }
+}
+
+impl Sub for Rational
+{
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Rational
+    {
+        self + -other
+    }
+}
+
+impl Mul for Rational
+{
+    type Output = Rational;
+
+    fn mul(self, other: Rational) -> Rational
+    {
+        checked(self.checked_mul(other))
+    }
+}
+
+impl Div for Rational
+{
+    type Output = Rational;
+
+    fn div(self, other: Rational) -> Rational
+    {
+        if other.num == 0
+        {
+            panic!("rational division by zero");
+        }
+
+        checked(self.checked_div(other))
+    }
+}
+
+impl Neg for Rational
+{
+    type Output = Rational;
+
+    fn neg(self) -> Rational
+    {
+        Rational { num: -self.num, den: self.den }
+    }
+}
+
+impl AddAssign for Rational
+{
+    fn add_assign(&mut self, other: Rational)
+    {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Rational
+{
+    fn sub_assign(&mut self, other: Rational)
+    {
+        *self = *self - other;
+    }
+}
+
+impl<'a> Add<&'a Rational> for &'a Rational
+{
+    type Output = Rational;
+
+    fn add(self, other: &Rational) -> Rational
+    {
+        *self + *other
+    }
+}
+
+impl<'a> Sub<&'a Rational> for &'a Rational
+{
+    type Output = Rational;
+
+    fn sub(self, other: &Rational) -> Rational
+    {
+        *self - *other
+    }
+}
+
+impl Sum for Rational
+{
+    fn sum<I>(iter: I) -> Rational where I: Iterator<Item=Rational>
+    {
+        iter.fold(Rational::zero(), |acc, r| acc + r)
+    }
+}
+
+impl<'a> Sum<&'a Rational> for Rational
+{
+    fn sum<I>(iter: I) -> Rational where I: Iterator<Item=&'a Rational>
+    {
+        iter.fold(Rational::zero(), |acc, r| acc + *r)
+    }
+}
+
+/// Writes "3", "-1/2" or "2/3".
+impl Display for Rational
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result
+    {
+        if self.den == 1
+        {
+            write!(f, "{}", self.num)
+        }
+        else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+/// Parses integers ("42"), decimals ("1.5") and fractions ("2/3").
+impl FromStr for Rational
+{
+    type Err = ParseRationalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        let s = s.trim();
+
+        if let Some(slash) = s.find('/')
+        {
+            let num = parse_integer(&s[..slash])?;
+            let den = parse_integer(&s[slash + 1..])?;
+
+            if den == 0
+            {
+                return Err(ParseRationalError::new("zero denominator"));
+            }
+
+            return Ok(Rational::new(num, den));
+        }
+
+        if let Some(dot) = s.find('.')
+        {
+            let (whole, frac) = (&s[..dot], &s[dot + 1..]);
+
+            if frac.is_empty() || !frac.bytes().all(|b| b.is_ascii_digit())
+            {
+                return Err(ParseRationalError::new("invalid decimal"));
+            }
+
+            let negative = whole.starts_with('-');
+            let whole = if whole.is_empty() || whole == "-" || whole == "+" { 0 } else { parse_integer(whole)? };
+            let scale = match 10i128.checked_pow(frac.len() as u32)
+            {
+                Some(scale) => scale,
+                None => return Err(ParseRationalError::new("too many decimal places"))
+            };
+            let frac = parse_integer(frac)?;
+
+            let magnitude = match whole.abs().checked_mul(scale).and_then(|w| w.checked_add(frac))
+            {
+                Some(magnitude) => magnitude,
+                None => return Err(ParseRationalError::new("number too large"))
+            };
+
+            return Ok(Rational::new(if negative { -magnitude } else { magnitude }, scale));
+        }
+
+        Ok(Rational::from(parse_integer(s)? as i64))
+    }
+}
+
+fn parse_integer(s: &str) -> Result<i128, ParseRationalError>
+{
+    let s = s.trim();
+
+    if s.is_empty()
+    {
+        return Err(ParseRationalError::new("missing number"));
+    }
+
+    match s.parse::<i64>()
+    {
+        Ok(n) => Ok(n as i128),
+        Err(_) => Err(ParseRationalError::new("invalid number"))
+    }
+}
+
+use std::error::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRationalError
+{
+    description: &'static str
+}
+
+impl ParseRationalError
+{
+    fn new(description: &'static str) -> Self
+    {
+        ParseRationalError { description: description }
+    }
+}
+
+impl Display for ParseRationalError
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result
+    {
+        write!(f, "{}", self.description)
+    }
+}
+
+impl Error for ParseRationalError
+{
+    fn description(&self) -> &str
+    {
+        self.description
+    }
+}
+
+
+#[cfg(test)]
+mod rational_tests
+{
+    use super::*;
+
+    #[test]
+    fn parses_weights()
+    {
+        assert_eq!("42".parse::<Rational>().unwrap(), Rational::from(42));
+        assert_eq!("1.5".parse::<Rational>().unwrap(), Rational::new(3, 2));
+        assert_eq!("-0.25".parse::<Rational>().unwrap(), Rational::new(-1, 4));
+        assert_eq!("2/3".parse::<Rational>().unwrap(), Rational::new(4, 6));
+        assert!("1/0".parse::<Rational>().is_err());
+        assert!("1.".parse::<Rational>().is_err());
+        assert!("A".parse::<Rational>().is_err());
+    }
+
+    #[test]
+    fn exact_arithmetic()
+    {
+        let third = Rational::new(1, 3);
+
+        assert_eq!(third + third + third, Rational::one());
+        assert_eq!(Rational::one() - third, Rational::new(2, 3));
+        assert_eq!(third * Rational::from(6), Rational::from(2));
+        assert_eq!(third / Rational::new(2, 3), Rational::new(1, 2));
+        assert!(Rational::new(2, 3) > Rational::new(3, 5));
+        assert_eq!(Rational::new(-7, 2).floor(), -4);
+        assert_eq!(Rational::new(-7, 2).ceil(), -3);
+        assert_eq!(format!("{}", Rational::new(6, -4)), "-3/2");
+    }
+}
@@ -0,0 +1,667 @@
+use std::collections::BTreeMap;
+
+use method::{VotingMethod, Input, Options, Outcome, MethodError};
+use sum_matrix::SumMatrix;
+use rational::Rational;
+use ranked_pairs::{RankedPairs, SequentialRankedPairs, Sequence};
+use schulze::Schulze;
+use schulze_stv::{SchulzeStv, DEFAULT_MAX_SETS};
+use minimax::Minimax;
+use copeland::Copeland;
+use kemeny::Kemeny;
+use slater::Slater;
+use ranked_robin::RankedRobin;
+use split_cycle::SplitCycle;
+use stable_voting::StableVoting;
+use borda::{Borda, Black, Nanson, Baldwin};
+use dodgson::{Dodgson, Young, DistanceResult};
+use coombs::Coombs;
+use bucklin::Bucklin;
+use rounds::EliminationResult;
+use irv::{Irv, TidemanAlternative, Benham, Woodall, SmithIrv};
+use maximal_lottery::MaximalLottery;
+use scores::rank_by;
+use compose::{Composed, Filter};
+
+
+/**
+Voting methods by name, so that the method can be chosen by configuration.
+Registry::default() holds every method in this crate; other methods can be added with register().
+Any registered method can also be run composed with a filter, by names like "smith//irv",
+"schwartz//borda" or "condorcet//smith//irv" (see Composed).
+*/
+pub struct Registry
+{
+    methods: BTreeMap<String, Box<dyn VotingMethod>>
+}
+
+impl Registry
+{
+    /// A registry without any methods.
+    pub fn new() -> Self
+    {
+        Registry { methods: BTreeMap::new() }
+    }
+
+    /// Adds a method under its name, returning the method it replaces, if any.
+    pub fn register(&mut self, method: Box<dyn VotingMethod>) -> Option<Box<dyn VotingMethod>>
+    {
+        self.methods.insert(method.name().to_owned(), method)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn VotingMethod>
+    {
+        self.methods.get(name).map(|method| &**method)
+    }
+
+    /// The registered names, in alphabetical order.
+    pub fn names(&self) -> Vec<&str>
+    {
+        self.methods.keys().map(|name| &name[..]).collect()
+    }
+
+    /// Looks up a method, composing it with filters if its name has any, such as "smith//irv".
+    pub fn resolve(&self, name: &str) -> Option<Box<dyn VotingMethod + '_>>
+    {
+        if let Some(split) = name.find("//")
+        {
+            if let Some(filter) = Filter::from_prefix(&name[..split])
+            {
+                return self.resolve(&name[(split + 2)..]).map(|method| Box::new(Composed::new(filter, method)) as Box<dyn VotingMethod>);
+            }
+        }
+
+        self.get(name).map(|method| Box::new(method) as Box<dyn VotingMethod>)
+    }
+
+    /// Looks up a method (see resolve()) and runs it.
+    pub fn run(&self, name: &str, input: &Input, options: &Options) -> Result<Outcome, MethodError>
+    {
+        match self.resolve(name)
+        {
+            Some(method) => method.run(input, options),
+            None => Err(MethodError::UnknownMethod(name.to_owned()))
+        }
+    }
+}
+
+impl Default for Registry
+{
+    fn default() -> Self
+    {
+        let mut registry = Registry::new();
+
+        let builtins = vec![
+            Builtin { name: "ranked-pairs", needs_ballots: false, run: ranked_pairs },
+            Builtin { name: "schulze", needs_ballots: false, run: schulze },
+            Builtin { name: "minimax", needs_ballots: false, run: minimax },
+            Builtin { name: "copeland", needs_ballots: false, run: copeland },
+            Builtin { name: "kemeny", needs_ballots: false, run: kemeny },
+            Builtin { name: "split-cycle", needs_ballots: false, run: split_cycle },
+            Builtin { name: "stable-voting", needs_ballots: false, run: stable_voting },
+            Builtin { name: "maximal-lottery", needs_ballots: false, run: maximal_lottery },
+            Builtin { name: "borda", needs_ballots: true, run: borda },
+            Builtin { name: "nanson", needs_ballots: true, run: nanson },
+            Builtin { name: "baldwin", needs_ballots: true, run: baldwin },
+            Builtin { name: "irv", needs_ballots: true, run: irv },
+            Builtin { name: "tideman-alternative", needs_ballots: true, run: tideman_alternative },
+            Builtin { name: "benham", needs_ballots: true, run: benham },
+            Builtin { name: "woodall", needs_ballots: true, run: woodall },
+            Builtin { name: "smith-irv", needs_ballots: true, run: smith_irv },
+            Builtin { name: "black", needs_ballots: true, run: black },
+            Builtin { name: "dodgson", needs_ballots: true, run: dodgson },
+            Builtin { name: "young", needs_ballots: true, run: young },
+            Builtin { name: "coombs", needs_ballots: true, run: coombs },
+            Builtin { name: "bucklin", needs_ballots: true, run: bucklin },
+            Builtin { name: "slater", needs_ballots: false, run: slater },
+            Builtin { name: "ranked-robin", needs_ballots: false, run: ranked_robin },
+            Builtin { name: "sequential-ranked-pairs", needs_ballots: false, run: sequential_ranked_pairs },
+            Builtin { name: "schulze-stv", needs_ballots: true, run: schulze_stv }
+        ];
+
+        for builtin in builtins
+        {
+            registry.register(Box::new(builtin));
+        }
+
+        registry
+    }
+}
+
+/// A method of this crate, adapted to VotingMethod by a plain function.
+struct Builtin
+{
+    name: &'static str,
+    needs_ballots: bool,
+    run: fn(&Input, &Options) -> Result<Outcome, MethodError>
+}
+
+impl VotingMethod for Builtin
+{
+    fn name(&self) -> &str
+    {
+        self.name
+    }
+
+    fn needs_ballots(&self) -> bool
+    {
+        self.needs_ballots
+    }
+
+    fn run(&self, input: &Input, options: &Options) -> Result<Outcome, MethodError>
+    {
+        (self.run)(input, options)
+    }
+}
+
+/// Ranks the candidates by repeatedly taking the winners of those left, for methods that only pick winners.
+fn rank_by_winners<F>(sum_matrix: &SumMatrix, winners: F) -> Result<Vec<Vec<String>>, MethodError>
+    where F: Fn(SumMatrix) -> Result<Vec<String>, MethodError>
+{
+    let mut remaining = sum_matrix.candidates();
+    let mut ranking = Vec::new();
+
+    while !remaining.is_empty()
+    {
+        let mut tier = winners(sum_matrix.restricted(&remaining))?;
+
+        if tier.is_empty()
+        {
+            tier = remaining.clone();
+        }
+
+        remaining.retain(|c| !tier.contains(c));
+        ranking.push(tier);
+    }
+
+    Ok(ranking)
+}
+
+fn singletons(order: Vec<String>) -> Vec<Vec<String>>
+{
+    order.into_iter().map(|c| vec![c]).collect()
+}
+
+fn ranked_pairs(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let sum_matrix = input.sum_matrix();
+    let ranking = RankedPairs::with_matrix(sum_matrix.clone(), options.strength_type()?).get_ranking();
+
+    let explanation = vec![
+        format!("Locked order: {}", ranking.join(" > ")),
+        format!("Smith set: {}", sum_matrix.smith_set().join(", "))
+    ];
+
+    Ok(Outcome::from_ranking(singletons(ranking), explanation))
+}
+
+/// A committee's seats in order as the first ranks, then everyone without a seat.
+fn sequential_ranked_pairs(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let sum_matrix = input.sum_matrix();
+    let seats = SequentialRankedPairs::with_matrix(sum_matrix.clone(), options.strength_type()?, options.parse("seats", 1)?)
+        .sequence(options.parse("sequence", Sequence::Order)?)
+        .get_result();
+
+    let winners: Vec<String> = seats.iter().map(|seat| seat.winner.clone()).collect();
+    let explanation = seats.iter().enumerate().map(|(i, seat)| format!("Seat {}: {} ({})", i + 1, seat.winner, seat.explanation)).collect();
+
+    let mut ranking = singletons(winners.clone());
+    let rest: Vec<String> = sum_matrix.candidates().into_iter().filter(|c| !winners.contains(c)).collect();
+    if !rest.is_empty() { ranking.push(rest); }
+
+    Ok(Outcome { winners: winners, ranking: ranking, explanation: explanation })
+}
+
+fn schulze(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let result = Schulze::with_matrix(input.sum_matrix(), options.strength_type()?).get_result();
+    let mut explanation = Vec::new();
+
+    for winner in &result.winners
+    {
+        for other in result.candidates.iter().filter(|&c| c != winner)
+        {
+            explanation.push(format!("Strongest path {} to {}: {}, back: {}", winner, other,
+                result.strength(winner, other).unwrap_or_default(), result.strength(other, winner).unwrap_or_default()));
+        }
+    }
+
+    Ok(Outcome { winners: result.winners, ranking: result.ranking, explanation: explanation })
+}
+
+fn minimax(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let result = Minimax::with_matrix(input.sum_matrix(), options.defeat_score()?).get_result();
+
+    let explanation = result.worst_defeats.iter()
+        .map(|d| format!("{}: worst defeat {} (by {})", d.candidate, d.score, d.opponents.join(", ")))
+        .collect();
+
+    Ok(Outcome { winners: result.winners, ranking: result.ranking, explanation: explanation })
+}
+
+fn copeland(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let alpha = options.parse("alpha", Rational::new(1, 2))?;
+    let second_order = options.parse("second-order", false)?;
+    let result = Copeland::with_matrix(input.sum_matrix(), alpha).second_order(second_order).get_result();
+
+    let mut explanation: Vec<String> = result.scores.iter().map(|&(ref c, score)| format!("{}: {}", c, score)).collect();
+
+    if let Some(ref scores) = result.second_order_scores
+    {
+        explanation.extend(scores.iter().map(|&(ref c, score)| format!("{}: second order {}", c, score)));
+    }
+
+    Ok(Outcome { winners: result.winners, ranking: result.ranking, explanation: explanation })
+}
+
+fn kemeny(input: &Input, _: &Options) -> Result<Outcome, MethodError>
+{
+    let result = Kemeny::with_matrix(input.sum_matrix()).get_result().map_err(|e| MethodError::Failed(e.to_string()))?;
+
+    let mut winners: Vec<String> = result.rankings.iter().filter_map(|r| r.first().cloned()).collect();
+    winners.sort();
+    winners.dedup();
+
+    let mut explanation = vec![format!("Score: {}", result.score)];
+    explanation.extend(result.rankings.iter().map(|r| format!("Optimal ranking: {}", r.join(" > "))));
+
+    if !result.complete
+    {
+        explanation.push("More optimal rankings exist".to_owned());
+    }
+
+    // Co-optimal rankings are tied, so the ranking is only certain when there's just one.
+    let ranking = if result.is_unique()
+    {
+        singletons(result.rankings[0].clone())
+    }
+    else {
+        let mut ranking = vec![winners.clone()];
+        let rest: Vec<String> = input.sum_matrix().candidates().into_iter().filter(|c| !winners.contains(c)).collect();
+        if !rest.is_empty() { ranking.push(rest); }
+        ranking
+    };
+
+    Ok(Outcome { winners: winners, ranking: ranking, explanation: explanation })
+}
+
+fn split_cycle(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let strength_type = options.strength_type()?;
+    let sum_matrix = input.sum_matrix();
+    let result = SplitCycle::with_matrix(sum_matrix.clone(), strength_type).get_result();
+
+    let explanation = result.discarded.iter()
+        .map(|d| format!("Discarded {} > {} ({}) in cycle {}", d.defeat.winner, d.defeat.loser, d.defeat.strength, d.cycle.join(" > ")))
+        .collect();
+
+    let ranking = rank_by_winners(&sum_matrix, |m| Ok(SplitCycle::with_matrix(m, strength_type).get_winners()))?;
+
+    Ok(Outcome { winners: result.winners, ranking: ranking, explanation: explanation })
+}
+
+fn stable_voting(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let sum_matrix = input.sum_matrix();
+    let failed = |e: ::stable_voting::StableVotingError| MethodError::Failed(e.to_string());
+    let max_states = options.parse("max-states", ::stable_voting::DEFAULT_MAX_STATES)?;
+    let result = StableVoting::with_matrix(sum_matrix.clone()).max_states(max_states).get_result().map_err(failed)?;
+
+    let mut explanation = Vec::new();
+
+    for winner in &result.winners
+    {
+        for step in &winner.chain
+        {
+            if let Some((ref removed, margin)) = step.removed
+            {
+                explanation.push(format!("{} wins among {}: margin {} over {}, who is removed",
+                    step.winner, step.candidates.join(", "), margin, removed));
+            }
+        }
+    }
+
+    let ranking = rank_by_winners(&sum_matrix, |m| StableVoting::with_matrix(m).max_states(max_states).get_winners().map_err(failed))?;
+
+    Ok(Outcome
+    {
+        winners: result.winners.into_iter().map(|w| w.candidate).collect(),
+        ranking: ranking,
+        explanation: explanation
+    })
+}
+
+fn maximal_lottery(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let result = MaximalLottery::with_matrix(input.sum_matrix()).get_result().map_err(|e| MethodError::Failed(e.to_string()))?;
+
+    let explanation = result.probabilities.iter().map(|&(ref c, p)| format!("{}: probability {}", c, p)).collect();
+    let ranking = rank_by(result.probabilities.clone());
+
+    // With a seed, the lottery is drawn; otherwise every candidate with a chance wins.
+    let winners = match options.get("seed")
+    {
+        Some(_) => result.draw(options.parse("seed", 0)?).into_iter().collect(),
+        None => result.support()
+    };
+
+    Ok(Outcome { winners: winners, ranking: ranking, explanation: explanation })
+}
+
+fn borda(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let result = Borda::with_election(input.election("borda")?).truncation(options.truncation()?).get_result();
+
+    let explanation = result.scores.iter().map(|&(ref c, score)| format!("{}: {}", c, score)).collect();
+
+    Ok(Outcome { winners: result.winners, ranking: result.ranking, explanation: explanation })
+}
+
+fn elimination(result: EliminationResult) -> Outcome
+{
+    let mut explanation = Vec::new();
+
+    for (i, round) in result.rounds.iter().enumerate()
+    {
+        explanation.push(format!("Round {}: {}", i + 1,
+            round.tallies.iter().map(|&(ref c, t)| format!("{} {}", c, t)).collect::<Vec<_>>().join(", ")));
+
+        if round.exhausted.is_positive()
+        {
+            explanation.push(format!("Exhausted: {}", round.exhausted));
+        }
+
+        if !round.eliminated.is_empty()
+        {
+            explanation.push(format!("Eliminated: {}", round.eliminated.join(", ")));
+        }
+
+        for transfer in &round.transfers
+        {
+            explanation.push(format!("Transferred {} from {} to {}", transfer.amount, transfer.from,
+                transfer.to.as_ref().map_or("exhausted", |to| &to[..])));
+        }
+    }
+
+    Outcome { winners: result.winners, ranking: result.ranking, explanation: explanation }
+}
+
+fn nanson(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    Ok(elimination(Nanson::with_election(input.election("nanson")?).truncation(options.truncation()?).get_result()))
+}
+
+fn baldwin(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    Ok(elimination(Baldwin::with_election(input.election("baldwin")?).truncation(options.truncation()?).get_result()))
+}
+
+fn irv(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    Ok(elimination(Irv::with_election(input.election("irv")?).tie_break(options.tie_break()?).get_result()))
+}
+
+fn tideman_alternative(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let election = input.election("tideman-alternative")?;
+
+    Ok(elimination(TidemanAlternative::with_election(election, options.dominant_set()?).tie_break(options.tie_break()?).get_result()))
+}
+
+fn benham(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    Ok(elimination(Benham::with_election(input.election("benham")?).tie_break(options.tie_break()?).get_result()))
+}
+
+fn woodall(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    Ok(elimination(Woodall::with_election(input.election("woodall")?).tie_break(options.tie_break()?).get_result()))
+}
+
+fn smith_irv(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    Ok(elimination(SmithIrv::with_election(input.election("smith-irv")?).tie_break(options.tie_break()?).get_result()))
+}
+
+fn black(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let result = Black::with_election(input.election("black")?).truncation(options.truncation()?).get_result();
+
+    let mut explanation = vec![match result.condorcet_winner
+    {
+        Some(ref winner) => format!("Condorcet winner: {}", winner),
+        None => "No Condorcet winner, so the Borda count decides".to_owned()
+    }];
+    explanation.extend(result.borda.scores.iter().map(|&(ref c, score)| format!("{}: Borda score {}", c, score)));
+
+    Ok(Outcome { winners: result.winners, ranking: result.ranking, explanation: explanation })
+}
+
+fn distance(result: DistanceResult) -> Outcome
+{
+    let mut explanation = vec![if result.exact { "Exact scores".to_owned() } else { "Approximate scores".to_owned() }];
+
+    explanation.extend(result.scores.iter().map(|&(ref c, score)| match score
+    {
+        Some(score) => format!("{}: {}", c, score),
+        None => format!("{}: can't become the Condorcet winner", c)
+    }));
+
+    Outcome { winners: result.winners, ranking: result.ranking, explanation: explanation }
+}
+
+fn dodgson(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let max_states = options.parse("max-states", ::dodgson::DEFAULT_MAX_STATES)?;
+
+    Ok(distance(Dodgson::with_election(input.election("dodgson")?).max_states(max_states).get_result()))
+}
+
+fn young(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let max_states = options.parse("max-states", ::dodgson::DEFAULT_MAX_STATES)?;
+
+    Ok(distance(Young::with_election(input.election("young")?).max_states(max_states).get_result()))
+}
+
+fn coombs(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let result = Coombs::with_election(input.election("coombs")?).tie_break(options.tie_break()?).get_result();
+    let mut outcome = elimination(result.count);
+
+    for (i, round) in result.last_places.iter().enumerate()
+    {
+        outcome.explanation.push(format!("Round {} last places: {}", i + 1,
+            round.tallies.iter().map(|&(ref c, t)| format!("{} {}", c, t)).collect::<Vec<_>>().join(", ")));
+    }
+
+    Ok(outcome)
+}
+
+fn bucklin(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    Ok(elimination(Bucklin::with_election(input.election("bucklin")?).tie_break(options.tie_break()?).get_result()))
+}
+
+fn slater(input: &Input, _: &Options) -> Result<Outcome, MethodError>
+{
+    let result = Slater::with_matrix(input.sum_matrix()).get_result().map_err(|e| MethodError::Failed(e.to_string()))?;
+
+    let mut explanation = vec![format!("Majorities reversed: {}", result.reversals)];
+    explanation.extend(result.rankings.iter().map(|r| format!("Optimal ranking: {}", r.join(" > "))));
+
+    if !result.complete
+    {
+        explanation.push("More optimal rankings exist".to_owned());
+    }
+
+    Ok(Outcome { winners: result.winners, ranking: result.ranking, explanation: explanation })
+}
+
+fn ranked_robin(input: &Input, _: &Options) -> Result<Outcome, MethodError>
+{
+    let result = RankedRobin::with_matrix(input.sum_matrix()).get_result();
+
+    let mut explanation: Vec<String> = result.wins.iter().zip(&result.margins)
+        .map(|(&(ref c, wins), &(_, margin))| format!("{}: {} wins, margin {}", c, wins, margin))
+        .collect();
+    explanation.extend(result.ties.iter().map(|tie| format!("Tied on wins: {}", tie.join(", "))));
+
+    Ok(Outcome { winners: result.winners, ranking: result.ranking, explanation: explanation })
+}
+
+/// The winning committee as the first rank. If several tie, those in every one come first, then those in some.
+fn schulze_stv(input: &Input, options: &Options) -> Result<Outcome, MethodError>
+{
+    let election = input.election("schulze-stv")?;
+    let result = SchulzeStv::with_election(election, options.parse("seats", 1)?)
+        .max_sets(options.parse("max-sets", DEFAULT_MAX_SETS)?)
+        .get_result()
+        .map_err(|e| MethodError::Failed(e.to_string()))?;
+
+    let mut explanation: Vec<String> = result.winners.iter().map(|set| format!("Winning set: {}", set.join(", "))).collect();
+    explanation.push(format!("Sets compared: {}", result.sets.len()));
+
+    if let Some(strength) = result.strength
+    {
+        explanation.push(format!("Beat-path strength: {}", strength));
+    }
+
+    let candidates = election.candidates();
+    let every: Vec<String> = candidates.iter().filter(|&c| result.winners.iter().all(|set| set.contains(c))).cloned().collect();
+    let some: Vec<String> = candidates.iter().filter(|&c| !every.contains(c) && result.winners.iter().any(|set| set.contains(c))).cloned().collect();
+    let rest: Vec<String> = candidates.iter().filter(|&c| !every.contains(c) && !some.contains(c)).cloned().collect();
+
+    let winners: Vec<String> = candidates.iter().filter(|&c| every.contains(c) || some.contains(c)).cloned().collect();
+    let ranking = vec![every, some, rest].into_iter().filter(|rank| !rank.is_empty()).collect();
+
+    Ok(Outcome { winners: winners, ranking: ranking, explanation: explanation })
+}
+
+
+#[cfg(test)]
+mod registry_tests
+{
+    use super::*;
+    use fixtures;
+
+    #[test]
+    fn every_method()
+    {
+        let election = fixtures::tennessee();
+        let sum_matrix = SumMatrix::new(&election);
+        let registry = Registry::default();
+
+        for name in registry.names()
+        {
+            let outcome = registry.run(name, &Input::Election(&election), &Options::new()).unwrap();
+
+            // Only IRV misses Nashville. Borda, Coombs and Bucklin aren't Condorcet-compliant either, but Nashville
+            // has the top Borda score, Coombs drops Memphis first and gives Nashville a majority,
+            // and Bucklin finds a majority for Nashville in the first two ranks.
+            let winner = if name == "irv" { "Knoxville" } else { "Nashville" };
+            assert_eq!(outcome.winners, vec![winner], "{}", name);
+            assert_eq!(outcome.ranking.iter().map(|r| r.len()).sum::<usize>(), 4, "{}", name);
+
+            let from_matrix = registry.run(name, &Input::Matrix(&sum_matrix), &Options::new());
+
+            if registry.get(name).unwrap().needs_ballots()
+            {
+                assert_eq!(from_matrix, Err(MethodError::NeedsBallots(name.to_owned())));
+            }
+            else {
+                assert_eq!(from_matrix.unwrap(), outcome, "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn options()
+    {
+        let election = fixtures::tennessee();
+        let registry = Registry::default();
+        let input = Input::Election(&election);
+
+        let options = Options::new().set("strength", "winning-votes");
+        assert!(registry.run("schulze", &input, &options).is_ok());
+
+        let options = Options::new().set("strength", "loudest");
+        assert_eq!(registry.run("schulze", &input, &options),
+            Err(MethodError::InvalidOption { name: "strength".to_owned(), value: "loudest".to_owned() }));
+
+        // Only Minimax scores by pairwise opposition.
+        let options = Options::new().set("strength", "pairwise-opposition");
+        assert!(registry.run("minimax", &input, &options).is_ok());
+        for name in &["ranked-pairs", "schulze", "split-cycle", "sequential-ranked-pairs"]
+        {
+            assert_eq!(registry.run(name, &input, &options),
+                Err(MethodError::InvalidOption { name: "strength".to_owned(), value: "pairwise-opposition".to_owned() }));
+        }
+
+        assert_eq!(registry.run("plurality", &input, &Options::new()), Err(MethodError::UnknownMethod("plurality".to_owned())));
+
+        let options = Options::new().set("seats", "2").set("sequence", "rerun");
+        let outcome = registry.run("sequential-ranked-pairs", &input, &options).unwrap();
+        assert_eq!(outcome.winners, vec!["Nashville", "Chattanooga"]);
+        assert_eq!(outcome.ranking, vec![vec!["Nashville"], vec!["Chattanooga"], vec!["Knoxville", "Memphis"]]);
+        assert_eq!(outcome.explanation[1], "Seat 2: Chattanooga (Wins the locked order of those left, Chattanooga > Knoxville > Memphis)");
+
+        let options = Options::new().set("seats", "3");
+        let outcome = registry.run("schulze-stv", &input, &options).unwrap();
+        assert_eq!(outcome.winners.len(), 3);
+        assert_eq!(outcome.explanation[1], "Sets compared: 4");
+
+        let options = Options::new().set("seats", "2").set("max-sets", "5");
+        assert_eq!(registry.run("schulze-stv", &input, &options),
+            Err(MethodError::Failed("too many candidate sets for Schulze STV (6 > 5)".to_owned())));
+
+        let options = Options::new().set("sequence", "backwards");
+        assert_eq!(registry.run("sequential-ranked-pairs", &input, &options),
+            Err(MethodError::InvalidOption { name: "sequence".to_owned(), value: "backwards".to_owned() }));
+    }
+
+    struct Alphabetical;
+
+    impl VotingMethod for Alphabetical
+    {
+        fn name(&self) -> &str { "alphabetical" }
+
+        fn run(&self, input: &Input, _: &Options) -> Result<Outcome, MethodError>
+        {
+            Ok(Outcome::from_ranking(singletons(input.sum_matrix().candidates()), Vec::new()))
+        }
+    }
+
+    #[test]
+    fn third_party()
+    {
+        let election = fixtures::tennessee();
+        let mut registry = Registry::default();
+
+        assert!(registry.register(Box::new(Alphabetical)).is_none());
+        assert_eq!(registry.run("alphabetical", &Input::Election(&election), &Options::new()).unwrap().winners, vec!["Chattanooga"]);
+
+        // Composed methods work with third-party methods too.
+        assert_eq!(registry.run("smith//alphabetical", &Input::Election(&election), &Options::new()).unwrap().winners, vec!["Nashville"]);
+    }
+
+    #[test]
+    fn composed()
+    {
+        let election = fixtures::tennessee();
+        let registry = Registry::default();
+
+        let method = registry.resolve("condorcet//smith//irv").unwrap();
+        assert_eq!(method.name(), "condorcet//smith//irv");
+        assert!(method.needs_ballots());
+        assert_eq!(registry.run("condorcet//irv", &Input::Election(&election), &Options::new()).unwrap().winners, vec!["Nashville"]);
+
+        assert!(registry.resolve("smith//").is_none());
+        assert!(registry.resolve("smith//nonsense").is_none());
+        assert_eq!(registry.run("copeland//irv", &Input::Election(&election), &Options::new()),
+            Err(MethodError::UnknownMethod("copeland//irv".to_owned())));
+    }
+}
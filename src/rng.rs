@@ -0,0 +1,88 @@
+use rational::Rational;
+
+/**
+A small seeded pseudo-random generator (SplitMix64).
+Used wherever a method needs randomness, so that results are reproducible from the seed.
+It is not suitable for anything security-related.
+*/
+#[derive(Debug, Clone)]
+pub struct Rng
+{
+    state: u64
+}
+
+impl Rng
+{
+    pub fn new(seed: u64) -> Self
+    {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64
+    {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in 0..n, without modulo bias.
+    /// Panics if 'n' is zero.
+    pub fn below(&mut self, n: usize) -> usize
+    {
+        assert!(n > 0, "Rng::below(0)");
+
+        let n = n as u64;
+        let zone = u64::max_value() - u64::max_value() % n;
+
+        loop
+        {
+            let r = self.next_u64();
+
+            if r < zone
+            {
+                return (r % n) as usize;
+            }
+        }
+    }
+
+    /// Picks an index with probability proportional to its weight,
+    /// or None if the weights add up to zero.
+    /// The random point is drawn to 32 bits, then compared exactly against the weights.
+    pub fn choose_weighted(&mut self, weights: &[Rational]) -> Option<usize>
+    {
+        let total: Rational = weights.iter().sum();
+
+        if !total.is_positive()
+        {
+            return None;
+        }
+
+        let point = Rational::new((self.next_u64() >> 32) as i128, 1 << 32) * total;
+        let mut cumulative = Rational::zero();
+
+        for (i, weight) in weights.iter().enumerate()
+        {
+            cumulative += *weight;
+
+            if weight.is_positive() && cumulative > point
+            {
+                return Some(i);
+            }
+        }
+
+        // Unreachable, since 'point' is below 'total'.
+        weights.iter().rposition(|w| w.is_positive())
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T])
+    {
+        for i in (1..items.len()).rev()
+        {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt::{self, Formatter, Display};
+
+use rational::Rational;
+use scores::rank_by;
+
+/// One round of an elimination count: everyone's tally, who was eliminated, and where their votes went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round
+{
+    /// The tally of each candidate still in the count, highest first.
+    pub tallies: Vec<(String, Rational)>,
+    pub eliminated: Vec<String>,
+    /// The weight of ballots that rank none of the candidates in the count.
+    /// Only counts that transfer ballots (like IRV) have any.
+    pub exhausted: Rational,
+    /// How the eliminated candidates' votes moved on for the next round. Only counts that transfer ballots have any.
+    pub transfers: Vec<Transfer>
+}
+
+impl Round
+{
+    pub fn new(mut tallies: Vec<(String, Rational)>, eliminated: Vec<String>) -> Self
+    {
+        tallies.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        Round { tallies: tallies, eliminated: eliminated, exhausted: Rational::zero(), transfers: Vec::new() }
+    }
+
+    pub fn tally(&self, cand: &str) -> Option<Rational>
+    {
+        self.tallies.iter().find(|&&(ref c, _)| c == cand).map(|&(_, t)| t)
+    }
+}
+
+/// Votes moving from an eliminated candidate to the next choice on their ballots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer
+{
+    pub from: String,
+    /// None when the ballots are exhausted.
+    pub to: Option<String>,
+    pub amount: Rational
+}
+
+/// Writes the tallies and the eliminated candidates, one candidate per line, then the exhausted ballots and transfers.
+impl Display for Round
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result
+    {
+        for &(ref cand, tally) in &self.tallies
+        {
+            let mark = if self.eliminated.contains(cand) { " (eliminated)" } else { "" };
+            writeln!(f, "{}: {}{}", cand, tally, mark)?;
+        }
+
+        if self.exhausted.is_positive()
+        {
+            writeln!(f, "exhausted: {}", self.exhausted)?;
+        }
+
+        for transfer in &self.transfers
+        {
+            writeln!(f, "{} -> {}: {}", transfer.from, transfer.to.as_ref().map_or("exhausted", |to| &to[..]), transfer.amount)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The outcome of an elimination count, such as Nanson, Baldwin or IRV.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EliminationResult
+{
+    /// Each round's tallies and who was eliminated. A final round eliminating no one means the rest tied,
+    /// or that a winner was found.
+    pub rounds: Vec<Round>,
+    pub winners: Vec<String>,
+    /// The winners first, then everyone else from the last eliminated to the first.
+    pub ranking: Vec<Vec<String>>
+}
+
+impl EliminationResult
+{
+    pub fn new(winners: Vec<String>, rounds: Vec<Round>) -> Self
+    {
+        let ranking = elimination_order(&winners, &rounds);
+
+        EliminationResult { rounds: rounds, winners: winners, ranking: ranking }
+    }
+}
+
+/**
+Orders candidates by how long they lasted in an elimination count: the winners first,
+then those who dropped out in each round, from the last round back to the first.
+Candidates who dropped out in the same round (including those still in the count when it ended)
+are split by their tally in that round; those eliminated without a tally come last.
+*/
+pub fn elimination_order(winners: &[String], rounds: &[Round]) -> Vec<Vec<String>>
+{
+    let mut ranking = Vec::new();
+    let mut placed: Vec<String> = winners.to_vec();
+
+    if !winners.is_empty()
+    {
+        ranking.push(winners.to_vec());
+    }
+
+    for round in rounds.iter().rev()
+    {
+        let tallied: Vec<(String, Option<Rational>)> = round.tallies.iter().map(|&(ref c, _)| c)
+            .chain(round.eliminated.iter().filter(|&c| round.tally(c).is_none()))
+            .filter(|&c| !placed.contains(c))
+            .map(|c| (c.clone(), round.tally(c)))
+            .collect();
+
+        placed.extend(tallied.iter().map(|&(ref c, _)| c.clone()));
+        ranking.extend(rank_by(tallied));
+    }
+
+    ranking
+}
@@ -0,0 +1,318 @@
+use sum_matrix::SumMatrix;
+use election::Election;
+use ranked_pairs::StrengthType;
+use rational::Rational;
+use rng::Rng;
+
+
+/**
+Schulze (beatpath) is a Condorcet-compliant voting method that uses a sum matrix.
+Each pairwise victory is a link from the winner to the loser, as strong as the victory (by margin or winning votes).
+A path is as strong as its weakest link. A beats B if A's strongest path to B is stronger than B's strongest path to A.
+This relation never has cycles, so there's always at least one candidate no one beats. They are the winners.
+*/
+pub struct Schulze
+{
+    sum_matrix: SumMatrix,
+    strength_type: StrengthType
+}
+
+impl Schulze
+{
+    pub fn with_matrix(sum_matrix: SumMatrix, strength_type: StrengthType) -> Self
+    {
+        Schulze{ sum_matrix: sum_matrix, strength_type: strength_type }
+    }
+
+    pub fn with_election(election: &Election, strength_type: StrengthType) -> Self
+    {
+        Schulze::with_matrix(SumMatrix::new(election), strength_type)
+    }
+
+    /// The strength of each direct victory: links[i][j] is how strongly candidates[i] beats candidates[j],
+    /// or zero if they don't.
+    fn links(&self, candidates: &[String]) -> Vec<Vec<Rational>>
+    {
+        let counts = self.sum_matrix.counts(candidates);
+        let n = candidates.len();
+
+        let mut links = vec![vec![Rational::zero(); n]; n];
+
+        for i in 0..n
+        {
+            for j in 0..n
+            {
+                if counts[i][j] > counts[j][i]
+                {
+                    links[i][j] = self.strength_type.strength(counts[i][j], counts[j][i]);
+                }
+            }
+        }
+
+        links
+    }
+
+    /**
+    Gets the strongest path strengths, the winners, and the full Schulze ranking.
+    */
+    pub fn get_result(&self) -> SchulzeResult
+    {
+        let candidates = self.sum_matrix.candidates();
+        let n = candidates.len();
+
+        // Floyd–Warshall, widening paths instead of shortening them.
+        let mut strengths = self.links(&candidates);
+
+        for i in 0..n
+        {
+            for j in 0..n
+            {
+                if i == j { continue; }
+
+                for k in 0..n
+                {
+                    if i == k || j == k { continue; }
+
+                    let through = ::std::cmp::min(strengths[j][i], strengths[i][k]);
+
+                    if through > strengths[j][k]
+                    {
+                        strengths[j][k] = through;
+                    }
+                }
+            }
+        }
+
+        SchulzeResult::new(candidates, strengths)
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+
+/// The outcome of a Schulze count.
+pub struct SchulzeResult
+{
+    /// The candidates, in the order used to index 'strengths'.
+    pub candidates: Vec<String>,
+    /// strengths[i][j] is the strength of the strongest path from candidates[i] to candidates[j].
+    pub strengths: Vec<Vec<Rational>>,
+    /// Everyone not beaten by anyone.
+    pub winners: Vec<String>,
+    /// The Schulze order, best first. Candidates in the same rank aren't beaten by anyone in a later rank,
+    /// or by each other.
+    pub ranking: Vec<Vec<String>>
+}
+
+impl SchulzeResult
+{
+    fn new(candidates: Vec<String>, strengths: Vec<Vec<Rational>>) -> Self
+    {
+        let n = candidates.len();
+        let mut left: Vec<usize> = (0..n).collect();
+        let mut ranking = Vec::new();
+
+        while !left.is_empty()
+        {
+            let (top, rest): (Vec<usize>, Vec<usize>) = left.iter()
+                .partition(|&&i| !left.iter().any(|&j| strengths[j][i] > strengths[i][j]));
+
+            ranking.push(top.iter().map(|&i| candidates[i].clone()).collect());
+            left = rest;
+        }
+
+        let winners = ranking.first().cloned().unwrap_or_default();
+
+        SchulzeResult { candidates: candidates, strengths: strengths, winners: winners, ranking: ranking }
+    }
+
+    fn index(&self, cand: &str) -> Option<usize>
+    {
+        self.candidates.iter().position(|c| c == cand)
+    }
+
+    /// The strength of the strongest path from 'from' to 'to'.
+    pub fn strength(&self, from: &str, to: &str) -> Option<Rational>
+    {
+        match (self.index(from), self.index(to))
+        {
+            (Some(i), Some(j)) => Some(self.strengths[i][j]),
+            _ => None
+        }
+    }
+
+    /**
+    Completes the Schulze order into a single linear order using a tie-breaking ranking of candidates (TBRC),
+    as Schulze recommends: going through the pairs in TBRC order, each pair the Schulze order leaves undecided
+    is decided by the TBRC, along with everything that then follows by transitivity.
+    Candidates missing from 'tie_breaker' are placed below those in it, by name.
+    */
+    pub fn break_ties(&self, tie_breaker: &[String]) -> Vec<String>
+    {
+        let n = self.candidates.len();
+
+        let mut order: Vec<usize> = Vec::new();
+        for i in tie_breaker.iter().filter_map(|c| self.index(c)).chain(0..n)
+        {
+            if !order.contains(&i) { order.push(i); }
+        }
+
+        let mut above = vec![vec![false; n]; n];
+
+        for i in 0..n
+        {
+            for j in 0..n
+            {
+                above[i][j] = self.strengths[i][j] > self.strengths[j][i];
+            }
+        }
+
+        for (pos, &a) in order.iter().enumerate()
+        {
+            for &b in &order[pos + 1..]
+            {
+                if above[a][b] || above[b][a]
+                {
+                    continue;
+                }
+
+                // Everything at or above 'a' now goes above everything at or below 'b'.
+                let uppers: Vec<usize> = (0..n).filter(|&x| x == a || above[x][a]).collect();
+                let lowers: Vec<usize> = (0..n).filter(|&y| y == b || above[b][y]).collect();
+
+                for &x in &uppers
+                {
+                    for &y in &lowers
+                    {
+                        above[x][y] = true;
+                    }
+                }
+            }
+        }
+
+        let mut linear: Vec<usize> = (0..n).collect();
+        linear.sort_by_key(|&i| ::std::cmp::Reverse(above[i].iter().filter(|&&b| b).count()));
+
+        linear.into_iter().map(|i| self.candidates[i].clone()).collect()
+    }
+}
+
+/**
+Builds a tie-breaking ranking of candidates from the ballots, as Schulze recommends:
+pick a ballot at random (in proportion to its weight) and use its order; candidates it leaves tied are
+ordered by another randomly picked ballot, and so on. Anything still tied once the ballots run out is shuffled.
+The same seed always gives the same ranking.
+*/
+pub fn tie_breaking_ranking(election: &Election, seed: u64) -> Vec<String>
+{
+    let mut rng = Rng::new(seed);
+    let mut groups: Vec<Vec<String>> = vec![election.candidates()];
+    let mut ballots: Vec<_> = election.votes().iter().filter(|b| b.weight.is_positive()).collect();
+
+    while groups.iter().any(|g| g.len() > 1)
+    {
+        let weights: Vec<Rational> = ballots.iter().map(|b| b.weight).collect();
+
+        let ballot = match rng.choose_weighted(&weights)
+        {
+            Some(i) => ballots.remove(i),
+            None => break
+        };
+
+        // Where the ballot ranks a candidate; unranked candidates share the last place.
+        let position = |cand: &String| ballot.ranks.iter().position(|rank| rank.contains(cand)).unwrap_or(ballot.ranks.len());
+
+        let mut split = Vec::new();
+
+        for mut group in groups
+        {
+            group.sort_by_key(|c| position(c));
+
+            let mut last = None;
+            for cand in group
+            {
+                let pos = position(&cand);
+
+                if last == Some(pos)
+                {
+                    let sub: &mut Vec<String> = split.last_mut().unwrap();
+                    sub.push(cand);
+                }
+                else {
+                    split.push(vec![cand]);
+                    last = Some(pos);
+                }
+            }
+        }
+
+        groups = split;
+    }
+
+    for group in &mut groups
+    {
+        rng.shuffle(group);
+    }
+
+    groups.into_iter().flat_map(|g| g.into_iter()).collect()
+}
+
+
+#[cfg(test)]
+mod schulze_tests
+{
+    use super::*;
+
+    fn example() -> Election
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        5:A>C>B>E>D
+        5:A>D>E>C>B
+        8:B>E>D>A>C
+        3:C>A>B>E>D
+        7:C>A>E>B>D
+        2:C>B>A>D>E
+        7:D>C>E>B>A
+        8:E>B>A>D>C").unwrap();
+
+        election
+    }
+
+    #[test]
+    fn winning_votes()
+    {
+        let result = Schulze::with_election(&example(), StrengthType::WinningVotes).get_result();
+
+        assert_eq!(result.winners, vec!["E"]);
+        assert_eq!(result.ranking, vec![vec!["E"], vec!["A"], vec!["C"], vec!["B"], vec!["D"]]);
+        assert_eq!(result.strength("E", "D"), Some(Rational::from(31)));
+        assert_eq!(result.strength("D", "E"), Some(Rational::from(24)));
+        assert_eq!(result.strength("A", "E"), Some(Rational::from(24)));
+        assert_eq!(result.strength("E", "A"), Some(Rational::from(25)));
+    }
+
+    #[test]
+    fn ties_are_broken()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        1:A>B>C
+        1:B>C>A
+        1:C>A>B").unwrap();
+
+        let result = Schulze::with_election(&election, StrengthType::Margin).get_result();
+
+        assert_eq!(result.winners, vec!["A", "B", "C"]);
+        assert_eq!(result.break_ties(&["C".to_owned(), "A".to_owned(), "B".to_owned()]), vec!["C", "A", "B"]);
+
+        let tbrc = tie_breaking_ranking(&election, 7);
+
+        assert_eq!(tbrc, tie_breaking_ranking(&election, 7));
+        assert_eq!(result.break_ties(&tbrc), tbrc);
+    }
+}
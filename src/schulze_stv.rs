@@ -0,0 +1,430 @@
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+
+use election::{Election, Ballot};
+use rational::Rational;
+use simplex::{LinearProgram, SimplexError};
+
+
+/// The most candidate sets SchulzeStv compares by default.
+pub const DEFAULT_MAX_SETS: usize = 1000;
+
+/**
+Schulze STV is a proportional multi-winner Condorcet method. It compares whole committees (sets of 'seats'
+candidates) that differ in one member: set A is preferred to B = A − a + b as strongly as the most
+support every member of A can be given at once, when each ballot's weight may be split between the members of A
+it ranks above b (this is vote management). So a faction can't win more seats than its size supports.
+The links between sets are ranked like Schulze's pairwise victories: a path is as strong as its weakest link,
+and a set wins if no other set has a stronger path to it than it has back.
+
+Each strength is found with a linear program. Every set is compared, so the work grows with the number of sets,
+which is limited by max_sets(). Preferences are the ballots' strict ones: candidates left off a ballot are ranked
+below those on it, and candidates ranked equal support neither. (Schulze's proportional completion of such
+ballots isn't done.)
+*/
+pub struct SchulzeStv
+{
+    election: Election,
+    seats: usize,
+    max_sets: usize
+}
+
+impl SchulzeStv
+{
+    pub fn with_election(election: &Election, seats: usize) -> Self
+    {
+        SchulzeStv{ election: election.clone(), seats: seats, max_sets: DEFAULT_MAX_SETS }
+    }
+
+    /// Limits how many candidate sets are compared (DEFAULT_MAX_SETS by default).
+    pub fn max_sets(mut self, max_sets: usize) -> Self
+    {
+        self.max_sets = max_sets;
+        self
+    }
+
+    /**
+    Gets the strongest paths between every pair of candidate sets, and the winning sets.
+    */
+    pub fn get_result(&self) -> Result<SchulzeStvResult, SchulzeStvError>
+    {
+        let candidates = self.election.candidates();
+        let n = candidates.len();
+
+        if self.seats == 0 || self.seats > n
+        {
+            return Err(SchulzeStvError::InvalidSeats { seats: self.seats, candidates: n });
+        }
+
+        let count = binomial(n, self.seats);
+
+        if count > self.max_sets
+        {
+            return Err(SchulzeStvError::TooManySets { sets: count, max_sets: self.max_sets });
+        }
+
+        let sets = combinations(n, self.seats);
+        let index: HashMap<&[usize], usize> = sets.iter().enumerate().map(|(i, set)| (&set[..], i)).collect();
+
+        // links[i] holds (j, strength) for each set j that differs from set i by one member.
+        let mut links: Vec<Vec<(usize, Rational)>> = Vec::new();
+
+        for set in &sets
+        {
+            let names: Vec<String> = set.iter().map(|&i| candidates[i].clone()).collect();
+            let mut out = Vec::new();
+
+            for b in (0..n).filter(|b| !set.contains(b))
+            {
+                let strength = link_strength(&self.election, &names, &candidates[b])?;
+
+                for a in set
+                {
+                    let mut other: Vec<usize> = set.iter().filter(|&c| c != a).cloned().chain(Some(b)).collect();
+                    other.sort();
+                    out.push((index[&other[..]], strength));
+                }
+            }
+
+            links.push(out);
+        }
+
+        let strengths: Vec<Vec<Rational>> = (0..sets.len()).map(|source| widest_paths(&links, source)).collect();
+
+        let sets: Vec<Vec<String>> = sets.iter().map(|set| set.iter().map(|&i| candidates[i].clone()).collect()).collect();
+
+        Ok(SchulzeStvResult::new(sets, strengths))
+    }
+
+    /// The first winning set, or an empty one if there's an error.
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().ok().and_then(|result| result.winners.into_iter().next()).unwrap_or_default()
+    }
+}
+
+/// Where 'cand' is on 'ballot': the index of its rank, or the number of ranks if it's unranked.
+fn position(ballot: &Ballot, cand: &str) -> usize
+{
+    ballot.ranks.iter().position(|rank| rank.iter().any(|c| c == cand)).unwrap_or(ballot.ranks.len())
+}
+
+/**
+The strength of the link from 'set' to any set that replaces one of its members with 'challenger':
+the most support T that every member of 'set' can get at once, when each ballot's weight can be split
+between the members it ranks above 'challenger'. This is a linear program over T and the split of each group
+of ballots with the same members above 'challenger'.
+Fails with Overflow if the program's exact arithmetic outgrows a Rational.
+*/
+pub fn link_strength(election: &Election, set: &[String], challenger: &str) -> Result<Rational, SchulzeStvError>
+{
+    let mut groups: BTreeMap<Vec<usize>, Rational> = BTreeMap::new();
+
+    for ballot in election.votes()
+    {
+        let below = position(ballot, challenger);
+        let above: Vec<usize> = (0..set.len()).filter(|&i| position(ballot, &set[i]) < below).collect();
+
+        if !above.is_empty()
+        {
+            *groups.entry(above).or_insert_with(Rational::zero) += ballot.weight;
+        }
+    }
+
+    // Someone no ballot ranks above the challenger gets no support at all.
+    if set.is_empty() || (0..set.len()).any(|i| !groups.keys().any(|group| group.contains(&i)))
+    {
+        return Ok(Rational::zero());
+    }
+
+    // Variable 0 is T; then each group has a variable for each member it supports.
+    let mut variables: Vec<usize> = Vec::new();
+    let mut group_constraints: Vec<(Vec<usize>, Rational)> = Vec::new();
+
+    for (group, &weight) in &groups
+    {
+        let start = variables.len() + 1;
+        variables.extend(group.iter().cloned());
+        group_constraints.push(((start..(start + group.len())).collect(), weight));
+    }
+
+    let width = variables.len() + 1;
+    let mut objective = vec![Rational::zero(); width];
+    objective[0] = Rational::one();
+
+    let mut lp = LinearProgram::new(objective);
+
+    // T is at most each member's support.
+    for member in 0..set.len()
+    {
+        let mut coefficients = vec![Rational::zero(); width];
+        coefficients[0] = Rational::one();
+
+        for (v, &m) in variables.iter().enumerate()
+        {
+            if m == member
+            {
+                coefficients[v + 1] = -Rational::one();
+            }
+        }
+
+        lp.add_constraint(coefficients, Rational::zero());
+    }
+
+    // Each group splits at most its weight.
+    for (vars, weight) in group_constraints
+    {
+        let mut coefficients = vec![Rational::zero(); width];
+
+        for v in vars
+        {
+            coefficients[v] = Rational::one();
+        }
+
+        lp.add_constraint(coefficients, weight);
+    }
+
+    // Bounded, since T is at most the total weight.
+    match lp.maximize()
+    {
+        Ok(solution) => Ok(solution.value),
+        Err(SimplexError::Overflow) => Err(SchulzeStvError::Overflow),
+        Err(e) => Err(SchulzeStvError::Solver(e))
+    }
+}
+
+/// The strength of the strongest path from 'source' to every set, where a path is as strong as its weakest link.
+fn widest_paths(links: &[Vec<(usize, Rational)>], source: usize) -> Vec<Rational>
+{
+    let mut strengths = vec![Rational::zero(); links.len()];
+    let mut done = vec![false; links.len()];
+    let mut queue: BinaryHeap<(Rational, usize)> = links[source].iter().map(|&(j, s)| (s, j)).collect();
+
+    done[source] = true;
+
+    while let Some((strength, set)) = queue.pop()
+    {
+        if done[set] { continue; }
+
+        done[set] = true;
+        strengths[set] = strength;
+
+        for &(next, link) in &links[set]
+        {
+            if !done[next]
+            {
+                queue.push((::std::cmp::min(strength, link), next));
+            }
+        }
+    }
+
+    strengths
+}
+
+/// Every k-subset of 0..n, each in increasing order, in lexicographic order.
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>>
+{
+    let mut all = Vec::new();
+    let mut current: Vec<usize> = (0..k).collect();
+
+    loop
+    {
+        all.push(current.clone());
+
+        // Advance the last index that can still move right, and reset the ones after it.
+        match (0..k).rev().find(|&i| current[i] < n - k + i)
+        {
+            Some(i) => {
+                current[i] += 1;
+                for j in (i + 1)..k
+                {
+                    current[j] = current[j - 1] + 1;
+                }
+            },
+            None => return all
+        }
+    }
+}
+
+/// n choose k, or usize::MAX if it's too large to count.
+fn binomial(n: usize, k: usize) -> usize
+{
+    let mut result: usize = 1;
+
+    for i in 0..k
+    {
+        result = match result.checked_mul(n - i)
+        {
+            Some(product) => product / (i + 1),
+            None => return usize::max_value()
+        };
+    }
+
+    result
+}
+
+/// The outcome of a Schulze STV count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchulzeStvResult
+{
+    /// Every candidate set compared, each sorted, in the order used to index 'strengths'.
+    pub sets: Vec<Vec<String>>,
+    /// strengths[i][j] is the strength of the strongest path from sets[i] to sets[j].
+    pub strengths: Vec<Vec<Rational>>,
+    /// The sets no other set beats; usually just one.
+    pub winners: Vec<Vec<String>>,
+    /// The beat-path strength of the first winning set: its weakest strongest path to any other set.
+    /// None if it's the only set.
+    pub strength: Option<Rational>
+}
+
+impl SchulzeStvResult
+{
+    fn new(sets: Vec<Vec<String>>, strengths: Vec<Vec<Rational>>) -> Self
+    {
+        let s = sets.len();
+
+        let winning: Vec<usize> = (0..s).filter(|&i| !(0..s).any(|j| strengths[j][i] > strengths[i][j])).collect();
+        let strength = winning.first().and_then(|&w| (0..s).filter(|&j| j != w).map(|j| strengths[w][j]).min());
+        let winners = winning.iter().map(|&i| sets[i].clone()).collect();
+
+        SchulzeStvResult { sets: sets, strengths: strengths, winners: winners, strength: strength }
+    }
+
+    fn index(&self, set: &[String]) -> Option<usize>
+    {
+        let mut sorted = set.to_vec();
+        sorted.sort();
+
+        self.sets.iter().position(|s| *s == sorted)
+    }
+
+    /// The strength of the strongest path from one set to another, in any order.
+    pub fn path_strength(&self, from: &[String], to: &[String]) -> Option<Rational>
+    {
+        match (self.index(from), self.index(to))
+        {
+            (Some(i), Some(j)) => Some(self.strengths[i][j]),
+            _ => None
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchulzeStvError
+{
+    /// No seats, or more seats than candidates.
+    InvalidSeats { seats: usize, candidates: usize },
+    /// There are more candidate sets than the limit set with max_sets().
+    TooManySets { sets: usize, max_sets: usize },
+    /// A link's linear program outgrew exact arithmetic.
+    Overflow,
+    /// A link's linear program failed some other way; its bound by the total weight should rule this out.
+    Solver(SimplexError)
+}
+
+use std::error::Error;
+use std::fmt::{self, Formatter, Display};
+
+impl Error for SchulzeStvError
+{
+    fn description(&self) -> &str
+    {
+        match *self
+        {
+            SchulzeStvError::InvalidSeats { .. } => "invalid number of seats",
+            SchulzeStvError::TooManySets { .. } => "too many candidate sets for Schulze STV",
+            SchulzeStvError::Overflow => "Schulze STV overflowed",
+            SchulzeStvError::Solver(_) => "Schulze STV link program failed"
+        }
+    }
+}
+
+impl Display for SchulzeStvError
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result
+    {
+        match *self
+        {
+            SchulzeStvError::InvalidSeats { seats, candidates } => write!(f, "can't fill {} seats from {} candidates", seats, candidates),
+            SchulzeStvError::TooManySets { sets, max_sets } => write!(f, "too many candidate sets for Schulze STV ({} > {})", sets, max_sets),
+            SchulzeStvError::Overflow => write!(f, "Schulze STV overflowed: a link strength is too large to compute exactly"),
+            SchulzeStvError::Solver(ref e) => write!(f, "Schulze STV link program failed: {}", e)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod schulze_stv_tests
+{
+    use super::*;
+    use fixtures;
+
+    fn names(names: &[&str]) -> Vec<String>
+    {
+        names.iter().map(|&c| c.to_owned()).collect()
+    }
+
+    #[test]
+    fn proportional()
+    {
+        let mut election = Election::new();
+
+        // A and B win every matchup against C, but C's 40% deserve one of the two seats.
+        election.add_ballots("
+        60:A>B>C
+        40:C>B>A").unwrap();
+
+        // The 60 can give A and B 30 each against C; A and C get 40 each against B.
+        assert_eq!(link_strength(&election, &names(&["A", "B"]), "C"), Ok(30.into()));
+        assert_eq!(link_strength(&election, &names(&["A", "C"]), "B"), Ok(40.into()));
+        assert_eq!(link_strength(&election, &names(&["B", "C"]), "A"), Ok(20.into()));
+
+        let result = SchulzeStv::with_election(&election, 2).get_result().unwrap();
+
+        assert_eq!(result.winners, vec![names(&["A", "C"])]);
+        assert_eq!(result.strength, Some(40.into()));
+        assert_eq!(result.path_strength(&names(&["B", "A"]), &names(&["A", "C"])), Some(30.into()));
+        assert_eq!(result.path_strength(&names(&["B", "C"]), &names(&["A", "C"])), Some(20.into()));
+    }
+
+    #[test]
+    fn one_seat()
+    {
+        let election = fixtures::tennessee();
+
+        // With one seat, a link is the number of voters who prefer one candidate to the other.
+        assert_eq!(link_strength(&election, &names(&["Nashville"]), "Memphis"), Ok(58.into()));
+
+        let stv = SchulzeStv::with_election(&election, 1);
+        assert_eq!(stv.get_winners(), vec!["Nashville"]);
+        assert_eq!(stv.get_result().unwrap().strength, Some(58.into()));
+
+        // Every candidate gets a seat, so nothing is compared.
+        let result = SchulzeStv::with_election(&election, 4).get_result().unwrap();
+        assert_eq!(result.winners, vec![names(&["Chattanooga", "Knoxville", "Memphis", "Nashville"])]);
+        assert_eq!(result.strength, None);
+    }
+
+    #[test]
+    fn limits()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        3:A>B>C>D
+        2:D>C>B>A").unwrap();
+
+        assert_eq!(SchulzeStv::with_election(&election, 2).max_sets(5).get_result(),
+            Err(SchulzeStvError::TooManySets { sets: 6, max_sets: 5 }));
+        assert_eq!(SchulzeStv::with_election(&election, 5).get_result(),
+            Err(SchulzeStvError::InvalidSeats { seats: 5, candidates: 4 }));
+        assert_eq!(SchulzeStv::with_election(&election, 0).get_winners(), Vec::<String>::new());
+
+        assert_eq!(combinations(4, 2).len(), 6);
+        assert_eq!(combinations(3, 3), vec![vec![0, 1, 2]]);
+        assert_eq!(binomial(60, 30), 118264581564861424);
+        assert_eq!(binomial(200, 100), usize::max_value());
+    }
+}
@@ -0,0 +1,23 @@
+/// Groups candidates into ranks by a score, highest first.
+/// Candidates with equal scores share a rank, in name order.
+pub fn rank_by<K>(mut scored: Vec<(String, K)>) -> Vec<Vec<String>> where K: Ord
+{
+    scored.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut ranking: Vec<Vec<String>> = Vec::new();
+    let mut last: Option<K> = None;
+
+    for (cand, score) in scored
+    {
+        if last.as_ref() == Some(&score)
+        {
+            ranking.last_mut().unwrap().push(cand);
+        }
+        else {
+            ranking.push(vec![cand]);
+            last = Some(score);
+        }
+    }
+
+    ranking
+}
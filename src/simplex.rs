@@ -0,0 +1,251 @@
+use rational::Rational;
+
+
+/**
+A linear program: maximise objective · x, subject to constraints of the form coefficients · x <= bound, and x >= 0.
+Every bound must be non-negative, so that x = 0 is a feasible starting point.
+
+It's solved exactly with the simplex method on a dense tableau of Rationals,
+using Bland's rule (the lowest-numbered improving variable enters, ties in the ratio test go to the
+lowest-numbered variable) so that degenerate problems can't cycle.
+It's intended for the small problems voting methods need, not for speed.
+*/
+#[derive(Debug, Clone)]
+pub struct LinearProgram
+{
+    objective: Vec<Rational>,
+    constraints: Vec<(Vec<Rational>, Rational)>
+}
+
+impl LinearProgram
+{
+    /// A program over objective.len() variables, without constraints yet.
+    pub fn new(objective: Vec<Rational>) -> Self
+    {
+        LinearProgram { objective: objective, constraints: Vec::new() }
+    }
+
+    /// Adds the constraint coefficients · x <= bound. Missing coefficients are zero.
+    pub fn add_constraint(&mut self, coefficients: Vec<Rational>, bound: Rational)
+    {
+        self.constraints.push((coefficients, bound));
+    }
+
+    /**
+    Finds an optimal x, the optimal value, and the optimal dual values (one per constraint).
+    Fails with Overflow, rather than panicking, if the exact arithmetic outgrows a Rational.
+    */
+    pub fn maximize(&self) -> Result<Solution, SimplexError>
+    {
+        let n = self.objective.len();
+        let m = self.constraints.len();
+
+        if let Some(i) = self.constraints.iter().position(|&(_, bound)| bound.is_negative())
+        {
+            return Err(SimplexError::NegativeBound(i));
+        }
+        if let Some(i) = self.constraints.iter().position(|&(ref coefficients, _)| coefficients.len() > n)
+        {
+            return Err(SimplexError::TooManyCoefficients(i));
+        }
+
+        // Each row holds the n variables, then m slack variables, then the bound.
+        let width = n + m + 1;
+        let mut rows: Vec<Vec<Rational>> = self.constraints.iter().enumerate().map(|(i, &(ref coefficients, bound))| {
+            let mut row = vec![Rational::zero(); width];
+            row[..coefficients.len()].copy_from_slice(coefficients);
+            row[n + i] = Rational::one();
+            row[width - 1] = bound;
+            row
+        }).collect();
+
+        // The reduced costs, with the (negated) objective value last.
+        let mut costs = vec![Rational::zero(); width];
+        for (j, &c) in self.objective.iter().enumerate()
+        {
+            costs[j] = -c;
+        }
+
+        let mut basis: Vec<usize> = (n..n + m).collect();
+
+        while let Some(entering) = (0..n + m).find(|&j| costs[j].is_negative())
+        {
+            let mut leaving: Option<(usize, Rational)> = None;
+
+            for i in 0..m
+            {
+                if !rows[i][entering].is_positive() { continue; }
+
+                let ratio = overflow(rows[i][width - 1].checked_div(rows[i][entering]))?;
+
+                leaving = match leaving
+                {
+                    Some((l, best)) if best < ratio || (best == ratio && basis[l] < basis[i]) => Some((l, best)),
+                    _ => Some((i, ratio))
+                };
+            }
+
+            let pivot_row = match leaving
+            {
+                Some((i, _)) => i,
+                None => return Err(SimplexError::Unbounded)
+            };
+
+            let pivot = rows[pivot_row][entering];
+            for value in &mut rows[pivot_row]
+            {
+                *value = overflow(value.checked_div(pivot))?;
+            }
+
+            let pivot_values = rows[pivot_row].clone();
+
+            for (i, row) in rows.iter_mut().enumerate()
+            {
+                let factor = row[entering];
+                if i == pivot_row || factor.is_zero() { continue; }
+
+                for j in 0..width
+                {
+                    row[j] = overflow(factor.checked_mul(pivot_values[j]).and_then(|p| row[j].checked_sub(p)))?;
+                }
+            }
+
+            let factor = costs[entering];
+            for j in 0..width
+            {
+                costs[j] = overflow(factor.checked_mul(pivot_values[j]).and_then(|p| costs[j].checked_sub(p)))?;
+            }
+
+            basis[pivot_row] = entering;
+        }
+
+        let mut values = vec![Rational::zero(); n];
+        for (i, &var) in basis.iter().enumerate()
+        {
+            if var < n
+            {
+                values[var] = rows[i][width - 1];
+            }
+        }
+
+        Ok(Solution
+        {
+            value: costs[width - 1],
+            values: values,
+            duals: costs[n..n + m].to_vec()
+        })
+    }
+}
+
+/// An optimal solution to a LinearProgram.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution
+{
+    /// The maximum of the objective.
+    pub value: Rational,
+    /// An x that reaches it: a vertex of the feasible region.
+    pub values: Vec<Rational>,
+    /// An optimal solution to the dual program: a price for each constraint.
+    pub duals: Vec<Rational>
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimplexError
+{
+    /// The objective can grow without limit.
+    Unbounded,
+    /// The constraint with this index has a negative bound.
+    NegativeBound(usize),
+    /// The constraint with this index has more coefficients than the program has variables.
+    TooManyCoefficients(usize),
+    /// The numbers in the tableau grew too large for a Rational.
+    Overflow
+}
+
+fn overflow<T>(value: Option<T>) -> Result<T, SimplexError>
+{
+    value.ok_or(SimplexError::Overflow)
+}
+
+use std::error::Error;
+use std::fmt::{self, Formatter, Display};
+
+impl Error for SimplexError
+{
+    fn description(&self) -> &str
+    {
+        match *self
+        {
+            SimplexError::Unbounded => "linear program is unbounded",
+            SimplexError::NegativeBound(_) => "constraint has a negative bound",
+            SimplexError::TooManyCoefficients(_) => "constraint has too many coefficients",
+            SimplexError::Overflow => "linear program overflowed"
+        }
+    }
+}
+
+impl Display for SimplexError
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result
+    {
+        match *self
+        {
+            SimplexError::Unbounded => write!(f, "linear program is unbounded"),
+            SimplexError::NegativeBound(i) => write!(f, "constraint {} has a negative bound", i),
+            SimplexError::TooManyCoefficients(i) => write!(f, "constraint {} has too many coefficients", i),
+            SimplexError::Overflow => write!(f, "linear program overflowed: the numbers grew too large to solve it exactly")
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod simplex_tests
+{
+    use super::*;
+
+    fn r(values: &[i32]) -> Vec<Rational>
+    {
+        values.iter().map(|&v| Rational::from(v)).collect()
+    }
+
+    #[test]
+    fn optimum()
+    {
+        // Maximise 3x + 5y with x <= 4, 2y <= 12, 3x + 2y <= 18: the optimum is x = 2, y = 6.
+        let mut lp = LinearProgram::new(r(&[3, 5]));
+        lp.add_constraint(r(&[1]), 4.into());
+        lp.add_constraint(r(&[0, 2]), 12.into());
+        lp.add_constraint(r(&[3, 2]), 18.into());
+
+        let solution = lp.maximize().unwrap();
+
+        assert_eq!(solution.value, 36.into());
+        assert_eq!(solution.values, r(&[2, 6]));
+        assert_eq!(solution.duals, vec![Rational::zero(), Rational::new(3, 2), Rational::one()]);
+    }
+
+    #[test]
+    fn degenerate()
+    {
+        // A classic cycling example for the largest-coefficient rule.
+        let mut lp = LinearProgram::new(vec![Rational::new(3, 4), (-150).into(), Rational::new(1, 50), (-6).into()]);
+        lp.add_constraint(vec![Rational::new(1, 4), (-60).into(), Rational::new(-1, 25), 9.into()], 0.into());
+        lp.add_constraint(vec![Rational::new(1, 2), (-90).into(), Rational::new(-1, 50), 3.into()], 0.into());
+        lp.add_constraint(r(&[0, 0, 1]), 1.into());
+
+        assert_eq!(lp.maximize().unwrap().value, Rational::new(1, 20));
+    }
+
+    #[test]
+    fn errors()
+    {
+        let mut lp = LinearProgram::new(r(&[1, 1]));
+        lp.add_constraint(r(&[1, -1]), 1.into());
+        assert_eq!(lp.maximize(), Err(SimplexError::Unbounded));
+
+        lp.add_constraint(r(&[1]), (-1).into());
+        assert_eq!(lp.maximize(), Err(SimplexError::NegativeBound(1)));
+    }
+}
@@ -0,0 +1,226 @@
+use sum_matrix::SumMatrix;
+use election::Election;
+use kemeny::{best_orders, MAX_EXACT_CANDIDATES};
+
+
+/**
+Slater's ranking is the order that reverses the fewest pairwise majorities.
+Unlike Kemeny–Young, it only looks at who wins each matchup, not by how much: every majority counts as 1,
+and a tied matchup counts for neither order. It's solved exactly with the same dynamic programming as Kemeny,
+so it's limited to MAX_EXACT_CANDIDATES.
+*/
+pub struct Slater
+{
+    sum_matrix: SumMatrix,
+    max_rankings: usize
+}
+
+impl Slater
+{
+    pub fn with_matrix(sum_matrix: SumMatrix) -> Self
+    {
+        Slater{ sum_matrix: sum_matrix, max_rankings: 1000 }
+    }
+
+    pub fn with_election(election: &Election) -> Self
+    {
+        Slater::with_matrix(SumMatrix::new(election))
+    }
+
+    /// Limits how many co-optimal rankings are listed (1000 by default).
+    pub fn max_rankings(mut self, max_rankings: usize) -> Self
+    {
+        self.max_rankings = max_rankings;
+        self
+    }
+
+    /**
+    Gets the fewest reversals, the optimal rankings, and the ranking they all agree on.
+    */
+    pub fn get_result(&self) -> Result<SlaterResult, SlaterError>
+    {
+        let candidates = self.sum_matrix.candidates();
+        let n = candidates.len();
+
+        if n > MAX_EXACT_CANDIDATES
+        {
+            return Err(SlaterError::TooManyCandidates(n));
+        }
+
+        let counts = self.sum_matrix.counts(&candidates);
+
+        // The majority tournament: 1 where i beats j, else 0.
+        let majorities: Vec<Vec<i128>> = (0..n)
+            .map(|i| (0..n).map(|j| if counts[i][j] > counts[j][i] { 1 } else { 0 }).collect())
+            .collect();
+
+        let total: i128 = majorities.iter().flat_map(|row| row.iter()).sum();
+        let solution = best_orders(&majorities, self.max_rankings);
+
+        let rankings: Vec<Vec<String>> = solution.orders.into_iter()
+            .map(|order| order.into_iter().map(|i| candidates[i].clone()).collect())
+            .collect();
+
+        let mut winners: Vec<String> = rankings.iter().filter_map(|r| r.first().cloned()).collect();
+        winners.sort();
+        winners.dedup();
+
+        Ok(SlaterResult
+        {
+            reversals: (total - solution.score) as usize,
+            ranking: common_ranking(&rankings),
+            rankings: rankings,
+            winners: winners,
+            complete: solution.complete
+        })
+    }
+}
+
+/// The outcome of a Slater count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlaterResult
+{
+    /// The number of pairwise majorities the optimal rankings reverse.
+    pub reversals: usize,
+    /// Every optimal ranking, best candidate first (up to the limit set with max_rankings()).
+    pub rankings: Vec<Vec<String>>,
+    /// The optimal rankings merged, with candidates sharing a rank where the rankings disagree (see common_ranking()).
+    pub ranking: Vec<Vec<String>>,
+    /// Every candidate first in an optimal ranking.
+    pub winners: Vec<String>,
+    /// False if there were more optimal rankings than the limit, in which case the merged ranking
+    /// only reflects those listed.
+    pub complete: bool
+}
+
+impl SlaterResult
+{
+    /// Whether exactly one ranking is optimal.
+    pub fn is_unique(&self) -> bool
+    {
+        self.complete && self.rankings.len() == 1
+    }
+}
+
+/**
+Merges several orders of the same candidates into one ranking with ties: it's split wherever the candidates
+above the split are the same in every order. So candidates share a rank unless every order separates them
+the same way; e.g. A>B>C>D and B>A>C>D give [A, B], [C], [D].
+*/
+pub fn common_ranking(orders: &[Vec<String>]) -> Vec<Vec<String>>
+{
+    let first = match orders.first()
+    {
+        Some(first) => first,
+        None => return Vec::new()
+    };
+
+    let mut ranking = Vec::new();
+    let mut rank: Vec<String> = Vec::new();
+
+    for k in 0..first.len()
+    {
+        rank.push(first[k].clone());
+
+        let mut top: Vec<&String> = first[..(k + 1)].iter().collect();
+        top.sort();
+
+        let split = orders.iter().all(|order| {
+            let mut other: Vec<&String> = order[..(k + 1)].iter().collect();
+            other.sort();
+            other == top
+        });
+
+        if split
+        {
+            rank.sort();
+            ranking.push(rank);
+            rank = Vec::new();
+        }
+    }
+
+    ranking
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlaterError
+{
+    /// More candidates than MAX_EXACT_CANDIDATES.
+    TooManyCandidates(usize)
+}
+
+use std::error::Error;
+use std::fmt::{self, Formatter, Display};
+
+impl Error for SlaterError
+{
+    fn description(&self) -> &str
+    {
+        match *self
+        {
+            SlaterError::TooManyCandidates(_) => "too many candidates for an exact Slater count"
+        }
+    }
+}
+
+impl Display for SlaterError
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result
+    {
+        match *self
+        {
+            SlaterError::TooManyCandidates(n) => write!(f, "too many candidates for an exact Slater count ({} > {})", n, MAX_EXACT_CANDIDATES)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod slater_tests
+{
+    use super::*;
+    use fixtures;
+
+    #[test]
+    fn tennessee()
+    {
+        let election = fixtures::tennessee();
+
+        let result = Slater::with_election(&election).get_result().unwrap();
+
+        assert!(result.is_unique());
+        assert_eq!(result.reversals, 0);
+        assert_eq!(result.ranking, vec![vec!["Nashville"], vec!["Chattanooga"], vec!["Knoxville"], vec!["Memphis"]]);
+    }
+
+    #[test]
+    fn cycle()
+    {
+        // B, C and D beat each other in a cycle and all beat A. Kemeny breaks the cycle at its weakest link,
+        // but to Slater every link is the same.
+        let election = fixtures::hybrids();
+
+        let mut result = Slater::with_election(&election).get_result().unwrap();
+        result.rankings.sort();
+
+        assert_eq!(result.reversals, 1);
+        assert_eq!(result.rankings, vec![vec!["B", "C", "D", "A"], vec!["C", "D", "B", "A"], vec!["D", "B", "C", "A"]]);
+        assert_eq!(result.ranking, vec![vec!["B", "C", "D"], vec!["A"]]);
+        assert_eq!(result.winners, vec!["B", "C", "D"]);
+    }
+
+    #[test]
+    fn merging()
+    {
+        let orders = |orders: &[&str]| -> Vec<Vec<String>>
+        {
+            orders.iter().map(|order| order.split('>').map(|c| c.to_owned()).collect()).collect()
+        };
+
+        assert_eq!(common_ranking(&orders(&["A>B>C>D", "B>A>C>D"])), vec![vec!["A", "B"], vec!["C"], vec!["D"]]);
+        assert_eq!(common_ranking(&orders(&["A>B>C", "B>C>A"])), vec![vec!["A", "B", "C"]]);
+        assert_eq!(common_ranking(&orders(&["A>B"])), vec![vec!["A"], vec!["B"]]);
+        assert!(common_ranking(&[]).is_empty());
+    }
+}
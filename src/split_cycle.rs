@@ -0,0 +1,209 @@
+use std::collections::VecDeque;
+
+use sum_matrix::SumMatrix;
+use election::Election;
+use ranked_pairs::StrengthType;
+use rational::Rational;
+
+
+/**
+Split Cycle (Holliday and Pacuit) is a Condorcet-compliant voting method that uses a sum matrix.
+In every cycle of pairwise victories, the weakest victories (by margin, as Holliday and Pacuit use, or winning votes) are discarded.
+A victory survives only if it's stronger than every path of victories leading back from the loser to the winner.
+The winners are everyone without a surviving defeat.
+Unlike RankedPairs, nothing depends on the order victories are considered in, so ties in strength need no breaking.
+*/
+pub struct SplitCycle
+{
+    sum_matrix: SumMatrix,
+    strength_type: StrengthType
+}
+
+impl SplitCycle
+{
+    pub fn with_matrix(sum_matrix: SumMatrix, strength_type: StrengthType) -> Self
+    {
+        SplitCycle{ sum_matrix: sum_matrix, strength_type: strength_type }
+    }
+
+    pub fn with_election(election: &Election, strength_type: StrengthType) -> Self
+    {
+        SplitCycle::with_matrix(SumMatrix::new(election), strength_type)
+    }
+
+    /**
+    Gets the undefeated candidates, the surviving defeats, and each discarded defeat with a cycle it was weakest in.
+    */
+    pub fn get_result(&self) -> SplitCycleResult
+    {
+        let candidates = self.sum_matrix.candidates();
+        let counts = self.sum_matrix.counts(&candidates);
+        let n = candidates.len();
+
+        let mut strengths: Vec<Vec<Option<Rational>>> = vec![vec![None; n]; n];
+
+        for i in 0..n
+        {
+            for j in 0..n
+            {
+                if counts[i][j] > counts[j][i]
+                {
+                    strengths[i][j] = Some(self.strength_type.strength(counts[i][j], counts[j][i]));
+                }
+            }
+        }
+
+        let mut defeats = Vec::new();
+        let mut discarded = Vec::new();
+
+        for i in 0..n
+        {
+            for j in 0..n
+            {
+                let strength = match strengths[i][j]
+                {
+                    Some(strength) => strength,
+                    None => continue
+                };
+
+                let defeat = Defeat { winner: candidates[i].clone(), loser: candidates[j].clone(), strength: strength };
+
+                // A path back from the loser made only of victories at least as strong.
+                match path(&strengths, j, i, strength)
+                {
+                    Some(back) => {
+                        let mut cycle = vec![candidates[i].clone()];
+                        cycle.extend(back.into_iter().map(|k| candidates[k].clone()));
+                        discarded.push(DiscardedDefeat { defeat: defeat, cycle: cycle });
+                    },
+                    None => defeats.push(defeat)
+                }
+            }
+        }
+
+        let winners = candidates.iter()
+            .filter(|&c| !defeats.iter().any(|d| d.loser == *c))
+            .cloned()
+            .collect();
+
+        SplitCycleResult { winners: winners, defeats: defeats, discarded: discarded }
+    }
+
+    pub fn get_winners(&self) -> Vec<String>
+    {
+        self.get_result().winners
+    }
+}
+
+/// The shortest path from 'from' to 'to' using only victories of at least 'min' strength, including both ends.
+fn path(strengths: &[Vec<Option<Rational>>], from: usize, to: usize, min: Rational) -> Option<Vec<usize>>
+{
+    let n = strengths.len();
+    let mut previous: Vec<Option<usize>> = vec![None; n];
+    let mut queue = VecDeque::new();
+
+    previous[from] = Some(from);
+    queue.push_back(from);
+
+    while let Some(node) = queue.pop_front()
+    {
+        if node == to
+        {
+            let mut path = vec![to];
+            let mut at = to;
+
+            while at != from
+            {
+                at = previous[at].unwrap();
+                path.push(at);
+            }
+
+            path.reverse();
+            return Some(path);
+        }
+
+        for next in 0..n
+        {
+            if previous[next].is_none() && strengths[node][next].map_or(false, |s| s >= min)
+            {
+                previous[next] = Some(node);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// A pairwise victory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Defeat
+{
+    pub winner: String,
+    pub loser: String,
+    pub strength: Rational
+}
+
+/// A victory Split Cycle discarded, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscardedDefeat
+{
+    pub defeat: Defeat,
+    /// A cycle the defeat was (one of) the weakest in, starting and ending with its winner:
+    /// winner, loser, ..., winner. Each candidate beats the next.
+    pub cycle: Vec<String>
+}
+
+/// The outcome of a Split Cycle count.
+pub struct SplitCycleResult
+{
+    /// Every candidate without a surviving defeat.
+    pub winners: Vec<String>,
+    pub defeats: Vec<Defeat>,
+    pub discarded: Vec<DiscardedDefeat>
+}
+
+
+#[cfg(test)]
+mod split_cycle_tests
+{
+    use super::*;
+
+    #[test]
+    fn discards_weakest()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        35:B>C>S
+        34:C>S>B
+        31:S>B>C").unwrap();
+
+        let result = SplitCycle::with_election(&election, StrengthType::Margin).get_result();
+
+        // B>C by 32, C>S by 38, S>B by 30: only S>B is discarded.
+        assert_eq!(result.winners, vec!["B"]);
+        assert_eq!(result.defeats.len(), 2);
+        assert_eq!(result.discarded, vec![DiscardedDefeat
+        {
+            defeat: Defeat { winner: "S".to_owned(), loser: "B".to_owned(), strength: 30.into() },
+            cycle: vec!["S".to_owned(), "B".to_owned(), "C".to_owned(), "S".to_owned()]
+        }]);
+    }
+
+    #[test]
+    fn equal_cycle()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        1:A>B>C
+        1:B>C>A
+        1:C>A>B").unwrap();
+
+        let result = SplitCycle::with_election(&election, StrengthType::Margin).get_result();
+
+        assert_eq!(result.winners, vec!["A", "B", "C"]);
+        assert_eq!(result.discarded.len(), 3);
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use sum_matrix::SumMatrix;
+use election::Election;
+use rational::Rational;
+
+
+/// The most candidates StableVoting can handle, since sub-elections are remembered by a 64-bit set.
+/// In practice, DEFAULT_MAX_STATES is the tighter limit.
+pub const MAX_CANDIDATES: usize = 64;
+
+/// How many sub-elections a count may decide before it gives up, by default.
+pub const DEFAULT_MAX_STATES: usize = 100_000;
+
+/**
+Stable Voting (Simple Stable Voting, Holliday and Pacuit) is a recursive Condorcet-compliant method that uses a sum matrix.
+Pairs of candidates (A, B) are considered from the largest margin of A over B to the smallest, as in RankedPairs.
+A wins if A is the winner of the election with B removed. Pairs with equal margins are considered together,
+so all of them that succeed are (tied) winners.
+Sub-elections are remembered by their set of candidates, so each is only counted once.
+There can still be exponentially many of them, so the count fails once it has decided max_states() of them.
+*/
+pub struct StableVoting
+{
+    sum_matrix: SumMatrix,
+    max_states: usize
+}
+
+impl StableVoting
+{
+    pub fn with_matrix(sum_matrix: SumMatrix) -> Self
+    {
+        StableVoting{ sum_matrix: sum_matrix, max_states: DEFAULT_MAX_STATES }
+    }
+
+    pub fn with_election(election: &Election) -> Self
+    {
+        StableVoting::with_matrix(SumMatrix::new(election))
+    }
+
+    /// Limits how many sub-elections are decided (DEFAULT_MAX_STATES by default).
+    pub fn max_states(mut self, max_states: usize) -> Self
+    {
+        self.max_states = max_states;
+        self
+    }
+
+    /**
+    Gets the winners, each with the chain of sub-elections that justifies them.
+    */
+    pub fn get_result(&self) -> Result<StableVotingResult, StableVotingError>
+    {
+        let candidates = self.sum_matrix.candidates();
+        let n = candidates.len();
+
+        if n > MAX_CANDIDATES
+        {
+            return Err(StableVotingError::TooManyCandidates(n));
+        }
+        if n == 0
+        {
+            return Ok(StableVotingResult { winners: Vec::new() });
+        }
+
+        let counts = self.sum_matrix.counts(&candidates);
+        let margins: Vec<Vec<Rational>> = (0..n).map(|i| (0..n).map(|j| counts[i][j] - counts[j][i]).collect()).collect();
+
+        let mut count = Count { margins: margins, memo: HashMap::new(), max_states: self.max_states };
+        let all = if n == 64 { u64::max_value() } else { (1u64 << n) - 1 };
+
+        let winners = count.winners(all)?;
+
+        let winners = winners.iter().map(|&(winner, _)| {
+            let mut chain = Vec::new();
+            let mut set = all;
+            let mut step = Some(winner);
+
+            while let Some(winner) = step
+            {
+                let removed = count.memo[&set].iter().find(|&&(w, _)| w == winner).and_then(|&(_, removed)| removed);
+
+                chain.push(SubElection
+                {
+                    candidates: members(set).into_iter().map(|i| candidates[i].clone()).collect(),
+                    winner: candidates[winner].clone(),
+                    removed: removed.map(|b| (candidates[b].clone(), count.margins[winner][b]))
+                });
+
+                step = match removed
+                {
+                    Some(b) => { set &= !(1 << b); Some(winner) },
+                    None => None
+                };
+            }
+
+            StableWinner { candidate: candidates[winner].clone(), chain: chain }
+        }).collect();
+
+        Ok(StableVotingResult { winners: winners })
+    }
+
+    pub fn get_winners(&self) -> Result<Vec<String>, StableVotingError>
+    {
+        Ok(self.get_result()?.winners.into_iter().map(|w| w.candidate).collect())
+    }
+}
+
+fn members(set: u64) -> Vec<usize>
+{
+    (0..64).filter(|&i| set & (1 << i) != 0).collect()
+}
+
+/// The state of one count: the margins and every sub-election decided so far.
+struct Count
+{
+    margins: Vec<Vec<Rational>>,
+    /// For each set of candidates, its winners and who was removed to justify each (None when alone).
+    memo: HashMap<u64, Vec<(usize, Option<usize>)>>,
+    max_states: usize
+}
+
+impl Count
+{
+    fn winners(&mut self, set: u64) -> Result<Vec<(usize, Option<usize>)>, StableVotingError>
+    {
+        if let Some(winners) = self.memo.get(&set)
+        {
+            return Ok(winners.clone());
+        }
+        if self.memo.len() >= self.max_states
+        {
+            return Err(StableVotingError::TooManyStates(self.max_states));
+        }
+
+        let cands = members(set);
+
+        let winners = if cands.len() == 1
+        {
+            vec![(cands[0], None)]
+        }
+        else {
+            let mut pairs: Vec<(usize, usize)> = Vec::new();
+            for &a in &cands
+            {
+                for &b in cands.iter().filter(|&&b| b != a)
+                {
+                    pairs.push((a, b));
+                }
+            }
+            pairs.sort_by(|&(a, b), &(c, d)| self.margins[c][d].cmp(&self.margins[a][b]));
+
+            let mut winners: Vec<(usize, Option<usize>)> = Vec::new();
+            let mut level = 0;
+
+            while winners.is_empty() && level < pairs.len()
+            {
+                let margin = self.margins[pairs[level].0][pairs[level].1];
+                let end = level + pairs[level..].iter().take_while(|&&(a, b)| self.margins[a][b] == margin).count();
+
+                for &(a, b) in &pairs[level..end]
+                {
+                    if winners.iter().any(|&(w, _)| w == a) { continue; }
+
+                    if self.winners(set & !(1 << b))?.iter().any(|&(w, _)| w == a)
+                    {
+                        winners.push((a, Some(b)));
+                    }
+                }
+
+                level = end;
+            }
+
+            winners.sort();
+            winners
+        };
+
+        self.memo.insert(set, winners.clone());
+        Ok(winners)
+    }
+}
+
+/// One step of a justification: 'winner' wins among 'candidates' because they beat 'removed' by the given margin,
+/// and win the sub-election without them (the next step).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubElection
+{
+    pub candidates: Vec<String>,
+    pub winner: String,
+    /// The removed candidate and the winner's margin over them; None once the winner is alone.
+    pub removed: Option<(String, Rational)>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StableWinner
+{
+    pub candidate: String,
+    /// The sub-elections justifying the win, from the full election down to the winner alone.
+    pub chain: Vec<SubElection>
+}
+
+/// The outcome of a Stable Voting count.
+pub struct StableVotingResult
+{
+    /// Usually one winner, but pairs with equal margins can produce a tie.
+    pub winners: Vec<StableWinner>
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StableVotingError
+{
+    /// More candidates than MAX_CANDIDATES.
+    TooManyCandidates(usize),
+    /// The count needed more sub-elections than this limit, set with max_states().
+    TooManyStates(usize)
+}
+
+use std::error::Error;
+use std::fmt::{self, Formatter, Display};
+
+impl Error for StableVotingError
+{
+    fn description(&self) -> &str
+    {
+        match *self
+        {
+            StableVotingError::TooManyCandidates(_) => "too many candidates for Stable Voting",
+            StableVotingError::TooManyStates(_) => "too many sub-elections for Stable Voting"
+        }
+    }
+}
+
+impl Display for StableVotingError
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result
+    {
+        match *self
+        {
+            StableVotingError::TooManyCandidates(n) => write!(f, "too many candidates for Stable Voting ({} > {})", n, MAX_CANDIDATES),
+            StableVotingError::TooManyStates(max) => write!(f, "too many sub-elections for Stable Voting (more than {})", max)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod stable_voting_tests
+{
+    use super::*;
+    use fixtures;
+
+    #[test]
+    fn chain()
+    {
+        let mut election = Election::new();
+
+        election.add_ballots("
+        35:B>C>S
+        34:C>S>B
+        31:S>B>C").unwrap();
+
+        let result = StableVoting::with_election(&election).get_result().unwrap();
+
+        // C>S (38), B>C (32) and S>B (30) all fail: each winner loses the two-way race without the loser.
+        // Of the negative margins, B over S (-30) comes first, and B beats C with S gone.
+        assert_eq!(result.winners.len(), 1);
+
+        let winner = &result.winners[0];
+
+        assert_eq!(winner.candidate, "B");
+        assert_eq!(winner.chain, vec![
+            SubElection { candidates: vec!["B".to_owned(), "C".to_owned(), "S".to_owned()], winner: "B".to_owned(), removed: Some(("S".to_owned(), (-30).into())) },
+            SubElection { candidates: vec!["B".to_owned(), "C".to_owned()], winner: "B".to_owned(), removed: Some(("C".to_owned(), 32.into())) },
+            SubElection { candidates: vec!["B".to_owned()], winner: "B".to_owned(), removed: None }
+        ]);
+    }
+
+    #[test]
+    fn condorcet_winner()
+    {
+        let election = fixtures::tennessee();
+
+        assert_eq!(StableVoting::with_election(&election).get_winners().unwrap(), vec!["Nashville"]);
+
+        // Four sub-elections decide it: everyone, then without Knoxville, Chattanooga and Memphis in turn.
+        assert_eq!(StableVoting::with_election(&election).max_states(4).get_winners().unwrap(), vec!["Nashville"]);
+        assert_eq!(StableVoting::with_election(&election).max_states(3).get_winners(), Err(StableVotingError::TooManyStates(3)));
+    }
+}
@@ -1,21 +1,33 @@
 
+use std::collections::BTreeSet;
+
 use matchup::Matchup;
 use table::{Values, Table};
-use election::Election;
+use election::{Election, Ballot};
+use rational::Rational;
 
 
 /// A table logging how many times each candidate defeats each other candidate.
+/// Each ballot adds its weight, so the tallies are exact even for fractional weights.
 /// Used for several Condorcet/Robin methods.
+#[derive(Clone)]
 pub struct SumMatrix
 {
-    table: Table<String, String, Matchup>
+    table: Table<String, String, Matchup>,
+    candidates: BTreeSet<String>
 }
 
 impl SumMatrix
 {
+    /// A matrix with no matchups, to be filled with add_vote() or add_wins().
+    pub fn empty() -> Self
+    {
+        SumMatrix { table: Table::new(), candidates: BTreeSet::new() }
+    }
+
     pub fn new(election: &Election) -> Self
     {
-        let mut sum_matrix = SumMatrix { table: Table::new() };
+        let mut sum_matrix = SumMatrix::empty();
 
         for vote in election.votes()
         {
@@ -25,33 +37,101 @@ impl SumMatrix
         return sum_matrix;
     }
 
-    pub fn add_vote(&mut self, vote: &Vec<Vec<String>>)
+    pub fn add_vote(&mut self, vote: &Ballot)
     {
-        for i in 0..vote.len()
+        let ranks = &vote.ranks;
+
+        for cand in ranks.iter().flat_map(|rank| rank.iter())
+        {
+            if !self.candidates.contains(cand)
+            {
+                self.candidates.insert(cand.clone());
+            }
+        }
+
+        for i in 0..ranks.len()
         {
-            for higher in &vote[i]
+            for higher in &ranks[i]
             {
-                for j in (i+1)..vote.len()
+                for lower_rank in &ranks[(i+1)..]
                 {
-                    for lower in &vote[j]
+                    for lower in lower_rank
                     {
-                        self.add_win(higher, lower);
+                        self.add_win(higher, lower, vote.weight);
                     }
                 }
             }
         }
     }
 
-    fn add_win(&mut self, for_cand: &String, against_cand: &String)
+    /// Adds 'amount' voters who prefer 'for_cand' over 'against_cand',
+    /// e.g. when reading a published pairwise matrix.
+    pub fn add_wins(&mut self, for_cand: &str, against_cand: &str, amount: Rational)
+    {
+        self.candidates.insert(for_cand.to_owned());
+        self.candidates.insert(against_cand.to_owned());
+        self.add_win(&for_cand.to_owned(), &against_cand.to_owned(), amount);
+    }
+
+    fn add_win(&mut self, for_cand: &String, against_cand: &String, weight: Rational)
     {
         // (A, B) and (B, A) should be treated as the same pair.
         let (a, b) = if for_cand < against_cand { (for_cand, against_cand) } else { (against_cand, for_cand) };
 
+        self.table.entry(a, b).or_emplace(a, b).add_win_for(for_cand, weight).unwrap()
+    }
+
+    /// Every candidate that has been ranked, in sorted order.
+    pub fn candidates(&self) -> Vec<String>
+    {
+        self.candidates.iter().cloned().collect()
+    }
+
+    /// How many voters prefer 'for_cand' over 'against_cand'.
+    pub fn wins(&self, for_cand: &str, against_cand: &str) -> Rational
+    {
+        let (a, b) = if for_cand < against_cand { (for_cand, against_cand) } else { (against_cand, for_cand) };
+
+        match self.table.get(a, b)
+        {
+            Some(matchup) => matchup.wins_for(for_cand).unwrap_or_default(),
+            None => Rational::zero()
+        }
+    }
+
+    /// The pairwise counts between 'candidates' as a dense matrix:
+    /// counts[i][j] is how many voters prefer candidates[i] over candidates[j].
+    pub fn counts(&self, candidates: &[String]) -> Vec<Vec<Rational>>
+    {
+        candidates.iter()
+            .map(|a| candidates.iter().map(|b| if a == b { Rational::zero() } else { self.wins(a, b) }).collect())
+            .collect()
+    }
+
+    /// A copy of the matrix with only the matchups between 'candidates'.
+    /// Pairs no ballot compared stay absent, as they are in the full matrix.
+    pub fn restricted(&self, candidates: &[String]) -> SumMatrix
+    {
+        let mut sum_matrix = SumMatrix::empty();
+
+        for (i, a) in candidates.iter().enumerate()
+        {
+            sum_matrix.candidates.insert(a.clone());
+
+            for b in &candidates[(i+1)..]
+            {
+                let (a, b) = if a < b { (a, b) } else { (b, a) };
+
+                if let Some(matchup) = self.table.get(a, b)
+                {
+                    sum_matrix.table.insert(a.clone(), b.clone(), matchup.clone());
+                }
+            }
+        }
 
-        self.table.entry(a, b).or_emplace(a, b).add_win_for(for_cand).unwrap()
+        sum_matrix
     }
 
-    
     /// Returns an iterator over the matchups.
     pub fn matchups(&self) -> Matchups
     {
@@ -3,7 +3,7 @@ use std::collections::hash_map;
 use std::hash::{Hash, Hasher};
 use std::borrow::Borrow;
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Pair<R,C>(R,C);
 
 #[derive(PartialEq, Eq, Hash)]
@@ -65,6 +65,7 @@ impl<'a, R:Hash+?Sized, C:Hash+?Sized> Hash for KeyPair<R,C> + 'a
     }
 }
 
+#[derive(Clone)]
 pub struct Table<R:Eq+Hash, C:Eq+Hash, V>
 {
     map: HashMap<Pair<R, C>, V>
@@ -77,6 +78,15 @@ impl<R:Eq+Hash, C:Eq+Hash, V> Table<R, C, V>
         Table { map: HashMap::new() }
     }
     
+    pub fn get<Q, U>(&self, row: &Q, column: &U) -> Option<&V> where
+        R: Borrow<Q>,
+        C: Borrow<U>,
+        Q: Eq+Hash+?Sized,
+        U: Eq+Hash+?Sized
+    {
+        self.map.get(&BPair(row, column) as &dyn KeyPair<Q,U>)
+    }
+
     pub fn get_mut<Q, U>(&mut self, row: &Q, column: &U) -> Option<&mut V> where
         R: Borrow<Q>,
         C: Borrow<U>,